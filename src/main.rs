@@ -1,4 +1,5 @@
 mod problems;
+mod utils;
 
 use std::io;
 use std::time::Instant;
@@ -15,6 +15,16 @@ mod problem_12;
 mod problem_13;
 mod problem_14;
 mod problem_15;
+mod problem_16;
+mod problem_17;
+mod problem_18;
+mod problem_19;
+mod problem_20;
+mod problem_21;
+mod problem_22;
+mod problem_23;
+mod problem_24;
+mod problem_25;
 
 pub fn year_2022() {
     let mut problem_number = String::new();
@@ -41,6 +51,16 @@ pub fn year_2022() {
         13 => problem_13::solve(),
         14 => problem_14::solve(),
         15 => problem_15::solve(),
+        16 => problem_16::solve(),
+        17 => problem_17::solve(),
+        18 => problem_18::solve(),
+        19 => problem_19::solve(),
+        20 => problem_20::solve(),
+        21 => problem_21::solve(),
+        22 => problem_22::solve(),
+        23 => problem_23::solve(),
+        24 => problem_24::solve(),
+        25 => problem_25::solve(),
         _ => println!("Invalid problem number"),
     }
 }
@@ -1,43 +1,276 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::env;
+use std::fmt::Write;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Lines};
 
-pub fn solve() {
-    println!("Problem 1");
-    let file = File::open("src/problems/year_2022/problem_1/input.txt").unwrap();
-    let reader = BufReader::new(file);
+use serde_json::{json, Value};
+
+const INPUT_PATH: &str = "src/problems/year_2022/problem_1/input.txt";
+const PERCENTILES: [u32; 6] = [10, 25, 50, 75, 90, 99];
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// One elf's inventory, summed as it is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group {
+    /// 0-based position of the elf in the input
+    pub index: usize,
+    pub total: i64,
+    pub item_count: usize,
+}
+
+/// Reads blank-line separated groups of numbers one group at a time, so the
+/// whole input never has to be in memory. Runs of blank lines count as one
+/// separator, and a last group without a trailing blank line is still returned.
+pub struct GroupedSums<R> {
+    lines: Lines<R>,
+    next_index: usize,
+}
+
+impl<R: BufRead> GroupedSums<R> {
+    pub fn new(reader: R) -> Self {
+        GroupedSums {
+            lines: reader.lines(),
+            next_index: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for GroupedSums<R> {
+    type Item = io::Result<Group>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut group = Group {
+            index: self.next_index,
+            total: 0,
+            item_count: 0,
+        };
+
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            if line.trim().is_empty() {
+                if group.item_count > 0 {
+                    break;
+                }
+                continue;
+            }
+            for item in line.split_whitespace() {
+                let calories: i64 = match item.parse() {
+                    Ok(calories) => calories,
+                    Err(e) => {
+                        let message = format!("Invalid calories '{}' for elf {}: {}", item, group.index + 1, e);
+                        return Some(Err(io::Error::new(io::ErrorKind::InvalidData, message)));
+                    }
+                };
+                group.total += calories;
+                group.item_count += 1;
+            }
+        }
+
+        if group.item_count == 0 {
+            return None;
+        }
+        self.next_index += 1;
+        Some(Ok(group))
+    }
+}
+
+/// Keeps the `k` largest totals seen so far in a min-heap, so memory stays
+/// O(k) however many elves there are
+pub struct TopK {
+    k: usize,
+    // Smallest total on top; among equal totals the later elf is dropped first
+    heap: BinaryHeap<Reverse<(i64, Reverse<usize>)>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub fn push(&mut self, index: usize, total: i64) {
+        if self.k == 0 {
+            return;
+        }
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    /// The kept (elf index, total) pairs, largest total first
+    pub fn into_ranking(self) -> Vec<(usize, i64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| (index, total))
+            .collect()
+    }
+}
+
+/// Ranks the `k` elves carrying the most calories, as (elf index, total)
+/// pairs with the largest total first
+pub fn rank_elves<R: BufRead>(reader: R, k: usize) -> io::Result<Vec<(usize, i64)>> {
+    let mut top = TopK::new(k);
+    for group in GroupedSums::new(reader) {
+        let group = group?;
+        top.push(group.index, group.total);
+    }
+    Ok(top.into_ranking())
+}
+
+/// A histogram bucket counting the elves whose total lies in `start..=end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: i64,
+    pub end: i64,
+    pub count: usize,
+}
+
+/// Descriptive statistics over every elf's inventory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    /// Number of items each elf carries, in input order
+    pub item_counts: Vec<usize>,
+    /// Every elf's total calories, in input order
+    pub totals: Vec<i64>,
+    pub mean: f64,
+    pub median: f64,
+    /// (percentile, total) pairs, interpolating between neighbouring elves
+    pub percentiles: Vec<(u32, f64)>,
+    pub histogram: Vec<Bucket>,
+}
 
-    let mut elf_calories = Vec::new();
-    let mut elf_total_calories = Vec::new();
+/// The `p`th percentile of sorted values, interpolating linearly between ranks
+fn percentile(sorted: &[i64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    let fraction = rank - low as f64;
+    sorted[low] as f64 + (sorted[high] - sorted[low]) as f64 * fraction
+}
+
+/// Splits the range of sorted values into `buckets` equally wide buckets
+fn histogram(sorted: &[i64], buckets: usize) -> Vec<Bucket> {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+    let width = ((max - min) / buckets as i64 + 1).max(1);
+    let mut histogram: Vec<Bucket> = (0..buckets as i64)
+        .map(|i| Bucket {
+            start: min + i * width,
+            end: min + (i + 1) * width - 1,
+            count: 0,
+        })
+        .take_while(|bucket| bucket.start <= max)
+        .collect();
+    for &value in sorted {
+        histogram[((value - min) / width) as usize].count += 1;
+    }
+    histogram
+}
+
+impl Statistics {
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut item_counts = Vec::new();
+        let mut totals = Vec::new();
+        for group in GroupedSums::new(reader) {
+            let group = group?;
+            item_counts.push(group.item_count);
+            totals.push(group.total);
+        }
 
-    for line in reader.lines() {
-        let line = line.unwrap();
-        if line.is_empty() {
-            // End of elf's food items, calculate total calories
-            let total_calories: i32 = elf_calories.iter().sum();
-            elf_total_calories.push(total_calories);
-            elf_calories.clear();
+        let mut sorted = totals.clone();
+        sorted.sort_unstable();
+        let mean = if totals.is_empty() {
+            0.0
         } else {
-            // Parse food items for current elf
-            let calories: Vec<i32> = line
-                .split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect();
-            elf_calories.extend(calories);
+            totals.iter().sum::<i64>() as f64 / totals.len() as f64
+        };
+
+        Ok(Statistics {
+            mean,
+            median: percentile(&sorted, 50.0),
+            percentiles: PERCENTILES.iter().map(|&p| (p, percentile(&sorted, p as f64))).collect(),
+            histogram: histogram(&sorted, HISTOGRAM_BUCKETS),
+            item_counts,
+            totals,
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let items = &self.item_counts;
+        writeln!(text, "Elves: {}", self.totals.len()).unwrap();
+        if let (Some(min), Some(max)) = (items.iter().min(), items.iter().max()) {
+            let mean_items = items.iter().sum::<usize>() as f64 / items.len() as f64;
+            writeln!(text, "Items per elf: min {}, mean {:.1}, max {}", min, mean_items, max).unwrap();
         }
+        writeln!(text, "Mean calories: {:.1}", self.mean).unwrap();
+        writeln!(text, "Median calories: {:.1}", self.median).unwrap();
+        for (p, value) in &self.percentiles {
+            writeln!(text, "  p{:<2}: {:.1}", p, value).unwrap();
+        }
+        writeln!(text, "Histogram:").unwrap();
+        let largest = self.histogram.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+        for bucket in &self.histogram {
+            let bar = "#".repeat((bucket.count * 40).div_ceil(largest));
+            writeln!(text, "  {:>6}-{:<6} {:>4} {}", bucket.start, bucket.end, bucket.count, bar).unwrap();
+        }
+        text
+    }
+
+    pub fn to_json(&self) -> Value {
+        let percentiles: serde_json::Map<String, Value> = self
+            .percentiles
+            .iter()
+            .map(|(p, value)| (format!("p{}", p), json!(value)))
+            .collect();
+        let histogram: Vec<Value> = self
+            .histogram
+            .iter()
+            .map(|b| json!({ "start": b.start, "end": b.end, "count": b.count }))
+            .collect();
+        json!({
+            "elves": self.totals.len(),
+            "item_counts": self.item_counts,
+            "totals": self.totals,
+            "mean": self.mean,
+            "median": self.median,
+            "percentiles": percentiles,
+            "histogram": histogram,
+        })
     }
+}
 
-    // Find elf with highest total calories
-    let (highest_elf, highest_calories) = elf_total_calories
-        .iter()
-        .enumerate()
-        .max_by_key(|(_, &calories)| calories)
-        .unwrap();
+/// Returns the requested report format if `--stats` (text) or `--stats=json` was passed
+fn stats_requested() -> Option<String> {
+    env::args().find_map(|arg| {
+        if arg == "--stats" {
+            Some("text".to_string())
+        } else {
+            arg.strip_prefix("--stats=").map(String::from)
+        }
+    })
+}
 
-    let mut sorted_elf_total_calories = elf_total_calories.clone();
-    sorted_elf_total_calories.sort();
-    sorted_elf_total_calories.reverse(); // Sort in descending order
+pub fn solve() {
+    println!("Problem 1");
+    let file = File::open(INPUT_PATH).unwrap();
+    let reader = BufReader::new(file);
 
-    let top_3_elves_total_calories: i32 = sorted_elf_total_calories.iter().take(3).sum();
+    let ranking = rank_elves(reader, 3).expect("Failed to read input file");
+    let (highest_elf, highest_calories) = ranking[0];
+    let top_3_elves_total_calories: i64 = ranking.iter().map(|&(_, total)| total).sum();
 
     println!(
         "Elf {} has the most food with {} calories",
@@ -48,4 +281,199 @@ pub fn solve() {
         "The sum of the total calories for the top 3 elves is {}",
         top_3_elves_total_calories
     );
+
+    if let Some(format) = stats_requested() {
+        let file = File::open(INPUT_PATH).unwrap();
+        let statistics = Statistics::from_reader(BufReader::new(file)).expect("Failed to read input file");
+        match format.as_str() {
+            "json" => println!("{}", statistics.to_json()),
+            "text" => print!("{}", statistics.to_text()),
+            _ => eprintln!("Warning: Unknown statistics format '{}'", format),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    fn groups(text: &str) -> Vec<Group> {
+        GroupedSums::new(text.as_bytes()).map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn ranks_example() {
+        let ranking = rank_elves(EXAMPLE.as_bytes(), 3).unwrap();
+        assert_eq!(ranking, vec![(3, 24000), (2, 11000), (4, 10000)]);
+    }
+
+    #[test]
+    fn keeps_last_group_without_trailing_blank_line() {
+        let with_newline = groups(EXAMPLE);
+        assert_eq!(groups(EXAMPLE.trim_end()), with_newline);
+        assert_eq!(groups("1\n2\n\n3"), groups("1\n2\n\n3\n\n"));
+        assert_eq!(
+            groups("\n\n1\n\n\n\n2 3"),
+            vec![
+                Group {
+                    index: 0,
+                    total: 1,
+                    item_count: 1,
+                },
+                Group {
+                    index: 1,
+                    total: 5,
+                    item_count: 2,
+                },
+            ]
+        );
+        assert!(groups("").is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_calories() {
+        let mut sums = GroupedSums::new("1\n\nx\n".as_bytes());
+        assert!(sums.next().unwrap().is_ok());
+        let error = sums.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn top_k_keeps_earlier_elves_on_ties() {
+        let mut top = TopK::new(2);
+        for (index, total) in [(0, 10), (1, 20), (2, 10), (3, 20), (4, 5)] {
+            top.push(index, total);
+        }
+        assert_eq!(top.into_ranking(), vec![(1, 20), (3, 20)]);
+
+        let mut top = TopK::new(2);
+        for index in 0..5 {
+            top.push(index, 7);
+        }
+        assert_eq!(top.into_ranking(), vec![(0, 7), (1, 7)]);
+    }
+
+    #[test]
+    fn top_zero_keeps_nothing() {
+        let mut top = TopK::new(0);
+        top.push(0, 100);
+        assert!(top.into_ranking().is_empty());
+        assert!(rank_elves(EXAMPLE.as_bytes(), 0).unwrap().is_empty());
+    }
+
+    fn statistics(text: &str) -> Statistics {
+        Statistics::from_reader(text.as_bytes()).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn summarises_example() {
+        let stats = statistics(EXAMPLE);
+        assert_eq!(stats.item_counts, vec![3, 1, 2, 3, 1]);
+        assert_eq!(stats.totals, vec![6000, 4000, 11000, 24000, 10000]);
+        assert_close(stats.mean, 11000.0);
+        assert_close(stats.median, 10000.0);
+
+        // Sorted totals are 4000, 6000, 10000, 11000, 24000
+        let expected = [(10, 4800.0), (25, 6000.0), (50, 10000.0), (75, 11000.0), (90, 18800.0), (99, 23480.0)];
+        assert_eq!(stats.percentiles.len(), expected.len());
+        for (&(p, value), &(expected_p, expected_value)) in stats.percentiles.iter().zip(&expected) {
+            assert_eq!(p, expected_p);
+            assert_close(value, expected_value);
+        }
+
+        let text = stats.to_text();
+        assert!(text.contains("Elves: 5\n"));
+        assert!(text.contains("Median calories: 10000.0\n"));
+    }
+
+    #[test]
+    fn buckets_example() {
+        let histogram = statistics(EXAMPLE).histogram;
+        // 20000 calories between the smallest and largest total, in buckets 2001 wide
+        assert_eq!(histogram.len(), HISTOGRAM_BUCKETS);
+        assert_eq!(
+            histogram[0],
+            Bucket {
+                start: 4000,
+                end: 6000,
+                count: 2,
+            }
+        );
+        assert_eq!(
+            histogram[9],
+            Bucket {
+                start: 22009,
+                end: 24009,
+                count: 1,
+            }
+        );
+        let counts: Vec<usize> = histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 0, 1, 1, 0, 0, 0, 0, 0, 1]);
+        for pair in histogram.windows(2) {
+            assert_eq!(pair[0].end + 1, pair[1].start);
+        }
+    }
+
+    #[test]
+    fn buckets_equal_totals() {
+        let stats = statistics("5\n\n2 3\n\n5\n");
+        assert_eq!(
+            stats.histogram,
+            vec![Bucket {
+                start: 5,
+                end: 5,
+                count: 3,
+            }]
+        );
+        assert_close(stats.mean, 5.0);
+        assert!(statistics("").histogram.is_empty());
+    }
+
+    #[test]
+    fn every_total_lands_in_a_bucket() {
+        let sorted: Vec<i64> = (0..37).map(|i| i * i * 7 - 300).collect();
+        for buckets in 1..=12 {
+            let histogram = histogram(&sorted, buckets);
+            assert!(histogram.len() <= buckets);
+            assert_eq!(histogram.iter().map(|b| b.count).sum::<usize>(), sorted.len());
+            for &value in &sorted {
+                let bucket = histogram.iter().find(|b| b.start <= value && value <= b.end);
+                assert!(bucket.is_some(), "{} has no bucket", value);
+            }
+        }
+    }
+
+    #[test]
+    fn reports_json() {
+        let json = statistics(EXAMPLE).to_json();
+        assert_eq!(json["elves"], 5);
+        assert_eq!(json["item_counts"], json!([3, 1, 2, 3, 1]));
+        assert_eq!(json["totals"], json!([6000, 4000, 11000, 24000, 10000]));
+        assert_eq!(json["mean"], 11000.0);
+        assert_eq!(json["median"], 10000.0);
+        assert_eq!(json["percentiles"]["p50"], 10000.0);
+        assert_eq!(json["percentiles"].as_object().unwrap().len(), PERCENTILES.len());
+        assert_eq!(json["histogram"].as_array().unwrap().len(), HISTOGRAM_BUCKETS);
+        assert_eq!(json["histogram"][0], json!({ "start": 4000, "end": 6000, "count": 2 }));
+    }
 }
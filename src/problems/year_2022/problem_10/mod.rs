@@ -1,12 +1,25 @@
 use std::fs;
+use std::fmt;
 use std::path::PathBuf;
 
-#[derive(Debug)]
+use crate::utils::ocr;
+use crate::utils::tui::{self, Simulation};
+
+#[derive(Debug, Clone, Copy)]
 enum Instruction {
     Noop,
     Addx(i32),
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Addx(value) => write!(f, "addx {}", value),
+        }
+    }
+}
+
 fn parse_input(input: &str) -> Vec<Instruction> {
     input
         .lines()
@@ -21,6 +34,62 @@ fn parse_input(input: &str) -> Vec<Instruction> {
         .collect()
 }
 
+/// Runs a program one clock cycle at a time
+#[derive(Debug, Clone)]
+struct Cpu {
+    instructions: Vec<Instruction>,
+    next_instruction: usize,
+    x: i32,
+    cycle: i32,
+    cycles_left: u32,
+    value_to_add_later: i32,
+}
+
+impl Cpu {
+    fn new(instructions: Vec<Instruction>) -> Self {
+        Cpu {
+            instructions,
+            next_instruction: 0,
+            x: 1,
+            cycle: 0,
+            cycles_left: 0,
+            value_to_add_later: 0,
+        }
+    }
+
+    /// Runs one cycle and returns its number along with the value of X *during*
+    /// the cycle, or `None` once the program has finished
+    fn tick(&mut self) -> Option<(i32, i32)> {
+        if self.cycles_left == 0 {
+            // Fetch the next instruction; noop takes 1 cycle, addx takes 2
+            let instruction = *self.instructions.get(self.next_instruction)?;
+            self.next_instruction += 1;
+            (self.cycles_left, self.value_to_add_later) = match instruction {
+                Instruction::Noop => (1, 0),
+                Instruction::Addx(value) => (2, value),
+            };
+        }
+
+        self.cycle += 1;
+        let during = (self.cycle, self.x);
+
+        // The instruction's effect on X shows from the start of the next cycle
+        self.cycles_left -= 1;
+        if self.cycles_left == 0 {
+            self.x += self.value_to_add_later;
+        }
+
+        Some(during)
+    }
+
+    /// The instruction that ran in the latest cycle
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.next_instruction
+            .checked_sub(1)
+            .map(|i| self.instructions[i])
+    }
+}
+
 fn is_measurement_cycle(cycle: i32) -> bool {
     cycle == 20 || (cycle > 20 && (cycle - 20) % 40 == 0)
 }
@@ -33,112 +102,39 @@ fn solve_part1(input_text: &str) -> i32 {
          eprintln!("Warning: No instructions found (input empty or parsing failed silently?)");
     }
 
-    let mut x: i32 = 1;
-    let mut cycle: i32 = 0; // Start cycle counter before the first cycle begins
+    let mut cpu = Cpu::new(instructions);
     let mut sum: i32 = 0;
-    let mut instruction_iter = instructions.into_iter(); // Use into_iter to consume instructions
 
-    let mut current_instruction_cycles_left = 0;
-    let mut value_to_add_later = 0; // Value from addx to apply when its cycles complete
-
-    // Loop indefinitely, driven by cycles, until instructions run out and ongoing operations finish
-    loop {
-        cycle += 1; // Advance to the current cycle number (1, 2, 3, ...)
-
-        // --- Check Signal Strength ---
-        // Check *during* this cycle (using X value from the start of the cycle)
+    while let Some((cycle, x)) = cpu.tick() {
+        // Signal strength uses the X value during the cycle
         if is_measurement_cycle(cycle) {
             let signal_strength = cycle * x;
             println!("Cycle {}: X = {}, Signal Strength = {}", cycle, x, signal_strength);
             sum += signal_strength;
         }
-
-        // --- Process Instruction Timing ---
-        if current_instruction_cycles_left > 0 {
-            // An instruction (must be addx) is ongoing. Decrement its remaining time.
-            current_instruction_cycles_left -= 1;
-            if current_instruction_cycles_left == 0 {
-                // This addx instruction just finished *at the end* of this cycle.
-                // Apply the change to X, which will be effective at the *start* of the next cycle.
-                x += value_to_add_later;
-            }
-        } else {
-            // No instruction ongoing, try to fetch the next one.
-            if let Some(instruction) = instruction_iter.next() {
-                match instruction {
-                    Instruction::Noop => {
-                        // noop starts and finishes in this single cycle.
-                        // No change to X, no cycles left.
-                        current_instruction_cycles_left = 0;
-                    }
-                    Instruction::Addx(value) => {
-                        // addx starts in this cycle, takes 2 cycles total.
-                        // 1 cycle remaining after this one.
-                        current_instruction_cycles_left = 1;
-                        // Store the value to add *after* the remaining cycle finishes.
-                        value_to_add_later = value;
-                    }
-                }
-            } else {
-                // No instructions left, and no instruction ongoing. We are done.
-                break;
-            }
-        }
     }
 
     sum
 }
 
+/// Whether the 3 pixel wide sprite centred on X covers the pixel being drawn
+fn is_lit(cycle: i32, x: i32) -> bool {
+    let crt_pos = (cycle - 1) % 40;
+    (x - 1..=x + 1).contains(&crt_pos)
+}
+
 fn solve_part2(input_text: &str) -> String {
-    let instructions = parse_input(input_text);
-    let mut x: i32 = 1;  // X register controls sprite position
-    let mut cycle: i32 = 0;
+    let mut cpu = Cpu::new(parse_input(input_text));
     let mut screen = String::with_capacity(246);  // 6 rows of 40 chars + newlines
-    let mut instruction_iter = instructions.into_iter();
-    let mut current_instruction_cycles_left = 0;
-    let mut value_to_add_later = 0;
-
-    loop {
-        cycle += 1;
-        
-        // Calculate CRT position (0-39 for each row)
-        let crt_pos = (cycle - 1) % 40;
-        
-        // Draw pixel based on sprite position
-        // Sprite is 3 pixels wide and centered on X
-        if (x - 1..=x + 1).contains(&crt_pos) {
-            screen.push('#');
-        } else {
-            screen.push('.');
-        }
-        
+
+    while let Some((cycle, x)) = cpu.tick() {
+        screen.push(if is_lit(cycle, x) { '#' } else { '.' });
+
         // Add newline after every 40 pixels
-        if crt_pos == 39 {
+        if (cycle - 1) % 40 == 39 {
             screen.push('\n');
         }
 
-        // Process instruction timing (same as part 1)
-        if current_instruction_cycles_left > 0 {
-            current_instruction_cycles_left -= 1;
-            if current_instruction_cycles_left == 0 {
-                x += value_to_add_later;
-            }
-        } else {
-            if let Some(instruction) = instruction_iter.next() {
-                match instruction {
-                    Instruction::Noop => {
-                        current_instruction_cycles_left = 0;
-                    }
-                    Instruction::Addx(value) => {
-                        current_instruction_cycles_left = 1;
-                        value_to_add_later = value;
-                    }
-                }
-            } else {
-                break;
-            }
-        }
-        
         // Stop after 240 cycles (6 rows of 40 pixels)
         if cycle >= 240 {
             break;
@@ -148,6 +144,72 @@ fn solve_part2(input_text: &str) -> String {
     screen
 }
 
+/// Draws the CRT one cycle at a time in the terminal UI
+#[derive(Clone)]
+struct CrtSimulation {
+    cpu: Cpu,
+    pixels: Vec<char>,
+    x_during: i32,
+    signal_sum: i32,
+}
+
+impl Simulation for CrtSimulation {
+    fn title(&self) -> String {
+        "CRT".to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        if self.cpu.cycle >= 240 {
+            return false;
+        }
+        let Some((cycle, x)) = self.cpu.tick() else {
+            return false;
+        };
+        self.pixels[(cycle - 1) as usize] = if is_lit(cycle, x) { '#' } else { '.' };
+        self.x_during = x;
+        if is_measurement_cycle(cycle) {
+            self.signal_sum += cycle * x;
+        }
+        true
+    }
+
+    fn render(&self, _width: usize, _height: usize) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .pixels
+            .chunks(40)
+            .map(|row| row.iter().collect())
+            .collect();
+
+        // Show where the sprite sits for the next cycle
+        let sprite: String = (0..40)
+            .map(|pos| if (self.cpu.x - 1..=self.cpu.x + 1).contains(&pos) { '#' } else { '.' })
+            .collect();
+        lines.push(String::new());
+        lines.push(sprite);
+        lines
+    }
+
+    fn inspect(&self) -> Vec<(String, String)> {
+        let cycle = self.cpu.cycle;
+        let instruction = match self.cpu.current_instruction() {
+            Some(instruction @ Instruction::Addx(_)) if self.cpu.cycles_left == 1 => {
+                format!("{} (1/2)", instruction)
+            }
+            Some(instruction @ Instruction::Addx(_)) => format!("{} (2/2)", instruction),
+            Some(instruction) => instruction.to_string(),
+            None => "-".to_string(),
+        };
+        vec![
+            ("Cycle".to_string(), cycle.to_string()),
+            ("X during".to_string(), self.x_during.to_string()),
+            ("X after".to_string(), self.cpu.x.to_string()),
+            ("Instruction".to_string(), instruction),
+            ("Signal strength".to_string(), (cycle * self.x_during).to_string()),
+            ("Measured sum".to_string(), self.signal_sum.to_string()),
+        ]
+    }
+}
+
 pub fn solve() {
     println!("Problem 10");
     let input_path = PathBuf::from("src/problems/year_2022/problem_10/input.txt");
@@ -157,4 +219,203 @@ pub fn solve() {
     
     let result2 = solve_part2(&input_text);
     println!("Part 2:\n{}", result2);
-} 
\ No newline at end of file
+    match ocr::recognise(&result2) {
+        Ok(letters) => println!("Part 2 letters: {}", letters),
+        Err(e) => eprintln!("Could not read the CRT letters: {}", e),
+    }
+
+    if tui::requested() {
+        let simulation = CrtSimulation {
+            cpu: Cpu::new(parse_input(&input_text)),
+            pixels: vec![' '; 240],
+            x_during: 1,
+            signal_sum: 0,
+        };
+        if let Err(e) = tui::run(vec![simulation]) {
+            eprintln!("Terminal UI failed: {}", e);
+        }
+    }
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+    #[test]
+    fn ticks_through_small_program() {
+        let mut cpu = Cpu::new(parse_input("noop\naddx 3\naddx -5\n"));
+        let during: Vec<(i32, i32)> = std::iter::from_fn(|| cpu.tick()).collect();
+        assert_eq!(during, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(cpu.x, -1);
+    }
+
+    #[test]
+    fn measures_example_signal() {
+        let mut cpu = Cpu::new(parse_input(EXAMPLE));
+        let measured: Vec<(i32, i32)> = std::iter::from_fn(|| cpu.tick())
+            .filter(|&(cycle, _)| is_measurement_cycle(cycle))
+            .collect();
+        assert_eq!(measured, vec![(20, 21), (60, 19), (100, 18), (140, 21), (180, 16), (220, 18)]);
+        assert_eq!(solve_part1(EXAMPLE), 13140);
+    }
+
+    #[test]
+    fn draws_example_screen() {
+        let expected = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
+        assert_eq!(solve_part2(EXAMPLE), expected);
+    }
+}
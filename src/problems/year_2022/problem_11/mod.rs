@@ -2,6 +2,9 @@ use std::fs;
 use std::path::PathBuf;
 use std::collections::VecDeque;
 
+use crate::utils::cycle::HashCycleDetector;
+use crate::utils::math;
+
 #[derive(Debug)]
 struct Monkey {
     items: VecDeque<i64>,
@@ -101,71 +104,79 @@ fn apply_operation(operation: &Operation, old: i64) -> i64 {
     }
 }
 
-fn solve_part1(input_text: &str) -> i64 {
-    let mut monkeys = parse_input(input_text);
-    let rounds = 20;
+/// Plays one round, with `relief` applied to each worry level after inspection
+fn play_round(monkeys: &mut [Monkey], relief: &impl Fn(i64) -> i64) {
+    for i in 0..monkeys.len() {
+        while let Some(item) = monkeys[i].items.pop_front() {
+            monkeys[i].inspection_count += 1;
 
-    for _ in 0..rounds {
-        for i in 0..monkeys.len() {
-            while let Some(item) = monkeys[i].items.pop_front() {
-                monkeys[i].inspection_count += 1;
-                
-                // Apply operation and divide by 3 (part 1)
-                let new_worry = apply_operation(&monkeys[i].operation, item) / 3;
-                
-                // Determine target monkey
-                let target = if new_worry % monkeys[i].test.divisor == 0 {
-                    monkeys[i].test.true_target
-                } else {
-                    monkeys[i].test.false_target
-                };
-                
-                // Throw item to target monkey
-                monkeys[target].items.push_back(new_worry);
-            }
+            let new_worry = relief(apply_operation(&monkeys[i].operation, item));
+
+            // Determine target monkey
+            let target = if new_worry % monkeys[i].test.divisor == 0 {
+                monkeys[i].test.true_target
+            } else {
+                monkeys[i].test.false_target
+            };
+
+            // Throw item to target monkey
+            monkeys[target].items.push_back(new_worry);
         }
     }
+}
 
-    // Calculate monkey business
-    let mut counts: Vec<i64> = monkeys.iter().map(|m| m.inspection_count).collect();
-    counts.sort_by(|a, b| b.cmp(a));
-    counts[0] * counts[1]
+/// The items held by each monkey, which fully determines every later round
+fn round_state(monkeys: &[Monkey]) -> Vec<VecDeque<i64>> {
+    monkeys.iter().map(|m| m.items.clone()).collect()
 }
 
-fn solve_part2(input_text: &str) -> i64 {
-    let mut monkeys = parse_input(input_text);
-    let rounds = 10000;
-    
-    // Calculate the product of all divisors for modulo arithmetic
-    let modulus: i64 = monkeys.iter().map(|m| m.test.divisor).product();
+/// Inspection counts per monkey after `rounds` rounds. Once the items return to
+/// an earlier arrangement the remaining rounds are extrapolated instead of played.
+fn inspection_counts(monkeys: &mut [Monkey], rounds: usize, relief: impl Fn(i64) -> i64) -> Vec<i64> {
+    let mut detector = HashCycleDetector::new();
+    let mut history: Vec<Vec<i64>> = vec![monkeys.iter().map(|m| m.inspection_count).collect()];
+    detector.observe(round_state(monkeys));
 
     for _ in 0..rounds {
-        for i in 0..monkeys.len() {
-            while let Some(item) = monkeys[i].items.pop_front() {
-                monkeys[i].inspection_count += 1;
-                
-                // Apply operation and use modulo arithmetic to keep numbers manageable
-                let new_worry = apply_operation(&monkeys[i].operation, item) % modulus;
-                
-                // Determine target monkey
-                let target = if new_worry % monkeys[i].test.divisor == 0 {
-                    monkeys[i].test.true_target
-                } else {
-                    monkeys[i].test.false_target
-                };
-                
-                // Throw item to target monkey
-                monkeys[target].items.push_back(new_worry);
-            }
+        play_round(monkeys, &relief);
+        history.push(monkeys.iter().map(|m| m.inspection_count).collect());
+
+        if let Some(cycle) = detector.observe(round_state(monkeys)) {
+            return (0..monkeys.len())
+                .map(|i| {
+                    let counts: Vec<i64> = history.iter().map(|round| round[i]).collect();
+                    cycle.extrapolate_metric(&counts, rounds)
+                })
+                .collect();
         }
     }
 
-    // Calculate monkey business
-    let mut counts: Vec<i64> = monkeys.iter().map(|m| m.inspection_count).collect();
+    history.pop().unwrap()
+}
+
+fn monkey_business(mut counts: Vec<i64>) -> i64 {
     counts.sort_by(|a, b| b.cmp(a));
     counts[0] * counts[1]
 }
 
+fn solve_part1(input_text: &str) -> i64 {
+    let mut monkeys = parse_input(input_text);
+
+    // Worry levels are divided by 3 after each inspection (part 1)
+    let counts = inspection_counts(&mut monkeys, 20, |worry| worry / 3);
+    monkey_business(counts)
+}
+
+fn solve_part2(input_text: &str) -> i64 {
+    let mut monkeys = parse_input(input_text);
+
+    // Every divisibility test is preserved modulo the lcm of all divisors
+    let modulus = math::lcm_all(monkeys.iter().map(|m| m.test.divisor));
+
+    let counts = inspection_counts(&mut monkeys, 10000, |worry| worry % modulus);
+    monkey_business(counts)
+}
+
 pub fn solve() {
     println!("Problem 11");
     let input_path = PathBuf::from("src/problems/year_2022/problem_11/input.txt");
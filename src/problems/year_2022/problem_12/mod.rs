@@ -2,6 +2,8 @@ use std::fs;
 use std::path::PathBuf;
 use std::collections::{VecDeque, HashSet};
 
+use crate::utils::visualize::{Frame, Visualizer};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Point {
     x: usize,
@@ -76,25 +78,33 @@ fn get_neighbors(point: Point, height_map: &HeightMap) -> Vec<Point> {
     neighbors
 }
 
-fn bfs(start: Point, end: Point, height_map: &HeightMap) -> Option<usize> {
+/// Finds a shortest path from `start` to `end`, including both ends
+fn bfs(start: Point, end: Point, height_map: &HeightMap) -> Option<Vec<Point>> {
     let mut queue = VecDeque::new();
     let mut visited = HashSet::new();
-    let mut distances = vec![vec![usize::MAX; height_map.heights[0].len()]; height_map.heights.len()];
+    let mut parents = vec![vec![None; height_map.heights[0].len()]; height_map.heights.len()];
     
     queue.push_back(start);
     visited.insert(start);
-    distances[start.y][start.x] = 0;
     
     while let Some(current) = queue.pop_front() {
         if current == end {
-            return Some(distances[current.y][current.x]);
+            // Walk back along the parents to rebuild the path
+            let mut path = vec![current];
+            let mut point = current;
+            while let Some(parent) = parents[point.y][point.x] {
+                path.push(parent);
+                point = parent;
+            }
+            path.reverse();
+            return Some(path);
         }
         
         for neighbor in get_neighbors(current, height_map) {
             if !visited.contains(&neighbor) {
                 visited.insert(neighbor);
                 queue.push_back(neighbor);
-                distances[neighbor.y][neighbor.x] = distances[current.y][current.x] + 1;
+                parents[neighbor.y][neighbor.x] = Some(current);
             }
         }
     }
@@ -102,9 +112,24 @@ fn bfs(start: Point, end: Point, height_map: &HeightMap) -> Option<usize> {
     None
 }
 
+/// Shades the heightmap from low (dark green) to high (white) with the path on top
+fn render_path(height_map: &HeightMap, path: &[Point]) -> Frame {
+    let mut frame = Frame::from_grid(&height_map.heights, |&h| {
+        let shade = h as u32 * 255 / 25;
+        [shade as u8, (60 + shade * 195 / 255) as u8, shade as u8]
+    });
+    for point in path {
+        frame.set(point.x, point.y, [220, 60, 60]);
+    }
+    frame.set(height_map.start.x, height_map.start.y, [60, 120, 230]);
+    frame.set(height_map.end.x, height_map.end.y, [250, 200, 40]);
+    frame.scaled(6)
+}
+
 fn solve_part1(input_text: &str) -> usize {
     let height_map = parse_input(input_text);
     bfs(height_map.start, height_map.end, &height_map)
+        .map(|path| path.len() - 1)
         .expect("No path found to the end")
 }
 
@@ -116,8 +141,8 @@ fn solve_part2(input_text: &str) -> usize {
     for y in 0..height_map.heights.len() {
         for x in 0..height_map.heights[0].len() {
             if height_map.heights[y][x] == 0 {
-                if let Some(steps) = bfs(Point { x, y }, height_map.end, &height_map) {
-                    min_steps = min_steps.min(steps);
+                if let Some(path) = bfs(Point { x, y }, height_map.end, &height_map) {
+                    min_steps = min_steps.min(path.len() - 1);
                 }
             }
         }
@@ -136,4 +161,11 @@ pub fn solve() {
     
     let result2 = solve_part2(&input_text);
     println!("Part 2: {}", result2);
+
+    if let Some(visualizer) = Visualizer::from_args("2022_day_12") {
+        let height_map = parse_input(&input_text);
+        if let Some(path) = bfs(height_map.start, height_map.end, &height_map) {
+            visualizer.save_image("path", &render_path(&height_map, &path));
+        }
+    }
 } 
\ No newline at end of file
@@ -1,100 +1,201 @@
-pub fn solve() {
-    // Helper to parse rock paths and fill a cave
-    fn fill_cave(paths: &[&str], width: usize, height: usize, with_floor: bool) -> Vec<Vec<char>> {
-        let mut cave = vec![vec!['.'; width]; height];
-        if with_floor {
-            for x in 0..width {
-                cave[height - 1][x] = '#';
-            }
+use crate::utils::tui::{self, Simulation};
+use crate::utils::visualize::{Frame, Visualizer};
+
+type Cave = Vec<Vec<char>>;
+type SandRun = fn(Cave, usize, &mut dyn FnMut(&[Vec<char>], usize)) -> (usize, Cave);
+
+// Helper to parse rock paths and fill a cave
+fn fill_cave(paths: &[&str], width: usize, height: usize, with_floor: bool) -> Cave {
+    let mut cave = vec![vec!['.'; width]; height];
+    if with_floor {
+        for cell in &mut cave[height - 1] {
+            *cell = '#';
         }
-        for path in paths {
-            let points: Vec<(usize, usize)> = path.split(" -> ")
-                .map(|p| {
-                    let coords: Vec<usize> = p.split(',')
-                        .map(|c| c.parse().unwrap())
-                        .collect();
-                    (coords[0], coords[1])
-                })
-                .collect();
-            for i in 0..points.len() - 1 {
-                let (x1, y1) = points[i];
-                let (x2, y2) = points[i + 1];
-                if x1 == x2 {
-                    let (start, end) = if y1 < y2 { (y1, y2) } else { (y2, y1) };
-                    for y in start..=end {
-                        cave[y][x1] = '#';
-                    }
-                } else if y1 == y2 {
-                    let (start, end) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
-                    for x in start..=end {
-                        cave[y1][x] = '#';
-                    }
+    }
+    for path in paths {
+        let points: Vec<(usize, usize)> = path.split(" -> ")
+            .map(|p| {
+                let coords: Vec<usize> = p.split(',')
+                    .map(|c| c.parse().unwrap())
+                    .collect();
+                (coords[0], coords[1])
+            })
+            .collect();
+        for i in 0..points.len() - 1 {
+            let (x1, y1) = points[i];
+            let (x2, y2) = points[i + 1];
+            if x1 == x2 {
+                let (start, end) = if y1 < y2 { (y1, y2) } else { (y2, y1) };
+                for row in &mut cave[start..=end] {
+                    row[x1] = '#';
+                }
+            } else if y1 == y2 {
+                let (start, end) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
+                for cell in &mut cave[y1][start..=end] {
+                    *cell = '#';
                 }
             }
         }
-        cave
     }
+    cave
+}
 
-    // Simulate sand for part 1 (no floor, stop when sand falls into abyss)
-    fn simulate_part1(mut cave: Vec<Vec<char>>, source_x: usize) -> usize {
-        let height = cave.len();
-        let width = cave[0].len();
-        let mut sand_count = 0;
-        'outer: loop {
-            let (mut x, mut y) = (source_x, 0);
-            loop {
-                if y + 1 >= height {
-                    break 'outer;
-                }
-                if cave[y + 1][x] == '.' {
-                    y += 1;
-                } else if x > 0 && cave[y + 1][x - 1] == '.' {
-                    y += 1;
-                    x -= 1;
-                } else if x + 1 < width && cave[y + 1][x + 1] == '.' {
-                    y += 1;
-                    x += 1;
-                } else {
-                    cave[y][x] = 'o';
-                    sand_count += 1;
-                    break;
-                }
-            }
+// Drop one unit of sand from the source, returning where it comes to rest,
+// or None if it falls out of the bottom of the cave
+fn drop_grain(cave: &mut [Vec<char>], source_x: usize) -> Option<(usize, usize)> {
+    let height = cave.len();
+    let width = cave[0].len();
+    let (mut x, mut y) = (source_x, 0);
+    loop {
+        if y + 1 >= height {
+            return None;
+        }
+        if cave[y + 1][x] == '.' {
+            y += 1;
+        } else if x > 0 && cave[y + 1][x - 1] == '.' {
+            y += 1;
+            x -= 1;
+        } else if x + 1 < width && cave[y + 1][x + 1] == '.' {
+            y += 1;
+            x += 1;
+        } else {
+            cave[y][x] = 'o';
+            return Some((x, y));
         }
-        sand_count
     }
+}
 
-    // Simulate sand for part 2 (with floor, stop when source is blocked)
-    fn simulate_part2(mut cave: Vec<Vec<char>>, source_x: usize) -> usize {
-        let height = cave.len();
-        let width = cave[0].len();
-        let mut sand_count = 0;
-        loop {
-            let (mut x, mut y) = (source_x, 0);
-            loop {
-                if y + 1 >= height {
-                    break;
-                }
-                if cave[y + 1][x] == '.' {
-                    y += 1;
-                } else if x > 0 && cave[y + 1][x - 1] == '.' {
-                    y += 1;
-                    x -= 1;
-                } else if x + 1 < width && cave[y + 1][x + 1] == '.' {
-                    y += 1;
-                    x += 1;
-                } else {
-                    cave[y][x] = 'o';
-                    sand_count += 1;
-                    if y == 0 && x == source_x {
-                        return sand_count;
-                    }
-                    break;
-                }
+// Simulate sand for part 1 (no floor, stop when sand falls into abyss)
+fn simulate_part1(
+    mut cave: Cave,
+    source_x: usize,
+    mut on_rest: impl FnMut(&[Vec<char>], usize),
+) -> (usize, Cave) {
+    let mut sand_count = 0;
+    while drop_grain(&mut cave, source_x).is_some() {
+        sand_count += 1;
+        on_rest(&cave, sand_count);
+    }
+    (sand_count, cave)
+}
+
+// Simulate sand for part 2 (with floor, stop when source is blocked)
+fn simulate_part2(
+    mut cave: Cave,
+    source_x: usize,
+    mut on_rest: impl FnMut(&[Vec<char>], usize),
+) -> (usize, Cave) {
+    let mut sand_count = 0;
+    while let Some(rest) = drop_grain(&mut cave, source_x) {
+        sand_count += 1;
+        on_rest(&cave, sand_count);
+        if rest == (source_x, 0) {
+            break;
+        }
+    }
+    (sand_count, cave)
+}
+
+// Renders the part of the cave sand can reach, which is at most
+// `height` columns either side of the source
+fn render(cave: &[Vec<char>], source_x: usize) -> Frame {
+    let height = cave.len();
+    let min_x = source_x.saturating_sub(height);
+    let max_x = (source_x + height).min(cave[0].len() - 1);
+    Frame::from_fn(max_x - min_x + 1, height, |x, y| match cave[y][x + min_x] {
+        '#' => [110, 110, 120],
+        'o' => [230, 190, 80],
+        _ => [20, 24, 40],
+    })
+    .scaled(2)
+}
+
+// Records a frame every `interval` units of sand, plus the final state
+fn visualize(
+    visualizer: &Visualizer,
+    name: &str,
+    cave: Cave,
+    source_x: usize,
+    interval: usize,
+    simulate: SandRun,
+) {
+    let mut frames = vec![render(&cave, source_x)];
+    let (_, cave) = simulate(cave, source_x, &mut |cave, sand_count| {
+        if sand_count % interval == 0 {
+            frames.push(render(cave, source_x));
+        }
+    });
+    let last = render(&cave, source_x);
+    visualizer.save_image(name, &last);
+    frames.push(last);
+    visualizer.save_animation(name, &frames, 5);
+}
+
+// Drops one unit of sand per step in the terminal UI
+#[derive(Clone)]
+struct SandSimulation {
+    title: &'static str,
+    cave: Cave,
+    source_x: usize,
+    sand_count: usize,
+    last_rest: Option<(usize, usize)>,
+    finished: bool,
+}
+
+impl Simulation for SandSimulation {
+    fn title(&self) -> String {
+        self.title.to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.last_rest = drop_grain(&mut self.cave, self.source_x);
+        match self.last_rest {
+            Some(rest) => {
+                self.sand_count += 1;
+                self.finished = rest == (self.source_x, 0);
             }
+            None => self.finished = true,
         }
+        true
+    }
+
+    fn render(&self, width: usize, height: usize) -> Vec<String> {
+        // Follow the latest unit of sand, clamped to the cave
+        let (focus_x, focus_y) = self.last_rest.unwrap_or((self.source_x, 0));
+        let cave_width = self.cave[0].len();
+        let left = focus_x.saturating_sub(width / 2).min(cave_width.saturating_sub(width));
+        let top = focus_y.saturating_sub(height / 2).min(self.cave.len().saturating_sub(height));
+
+        self.cave
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(height)
+            .map(|(y, row)| {
+                (left..(left + width).min(cave_width))
+                    .map(|x| if (x, y) == (self.source_x, 0) && row[x] == '.' { '+' } else { row[x] })
+                    .collect()
+            })
+            .collect()
     }
 
+    fn inspect(&self) -> Vec<(String, String)> {
+        let last_rest = match self.last_rest {
+            Some((x, y)) => format!("({}, {})", x, y),
+            None if self.finished => "fell into the abyss".to_string(),
+            None => "-".to_string(),
+        };
+        vec![
+            ("Units at rest".to_string(), self.sand_count.to_string()),
+            ("Last unit".to_string(), last_rest),
+        ]
+    }
+}
+
+pub fn solve() {
     let input = include_str!("input.txt");
     let paths: Vec<&str> = input.lines().collect();
     let highest_y = paths.iter()
@@ -105,10 +206,34 @@ pub fn solve() {
     let source_x = 500;
     // Part 1: no floor, cave height just enough to fit rocks
     let cave1 = fill_cave(&paths, width, highest_y + 3, false);
-    let part1 = simulate_part1(cave1, source_x);
+    let (part1, _) = simulate_part1(cave1.clone(), source_x, |_, _| {});
     // Part 2: with floor, cave height = highest_y + 3
     let cave2 = fill_cave(&paths, width, highest_y + 3, true);
-    let part2 = simulate_part2(cave2, source_x);
+    let (part2, _) = simulate_part2(cave2.clone(), source_x, |_, _| {});
     println!("Part 1: Units of sand that come to rest before falling into the abyss: {}", part1);
     println!("Part 2: Units of sand that come to rest before source is blocked: {}", part2);
-}
\ No newline at end of file
+
+    if let Some(visualizer) = Visualizer::from_args("2022_day_14") {
+        visualize(&visualizer, "part1", cave1.clone(), source_x, 5, |cave, x, on_rest| {
+            simulate_part1(cave, x, on_rest)
+        });
+        visualize(&visualizer, "part2", cave2.clone(), source_x, 200, |cave, x, on_rest| {
+            simulate_part2(cave, x, on_rest)
+        });
+    }
+
+    if tui::requested() {
+        let simulation = |title, cave| SandSimulation {
+            title,
+            cave,
+            source_x,
+            sand_count: 0,
+            last_rest: None,
+            finished: false,
+        };
+        let simulations = vec![simulation("No floor", cave1), simulation("With floor", cave2)];
+        if let Err(e) = tui::run(simulations) {
+            eprintln!("Terminal UI failed: {}", e);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+struct Valve {
+    name: String,
+    flow_rate: u32,
+    tunnels: Vec<String>,
+}
+
+/// The valves worth opening, with travel times between every pair of them
+#[derive(Debug)]
+struct ValveNetwork {
+    flow_rates: Vec<u32>,
+    // distances[i][j] is the number of minutes to walk from valve i to valve j
+    distances: Vec<Vec<u32>>,
+    // Distance from the starting valve AA to each valve
+    start_distances: Vec<u32>,
+}
+
+fn parse_line(line: &str) -> Valve {
+    // Example: Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+    let (valve_part, tunnel_part) = line
+        .split_once("; ")
+        .unwrap_or_else(|| panic!("Invalid line format: '{}'", line));
+    let name = valve_part.split_whitespace().nth(1).unwrap().to_string();
+    let flow_rate = valve_part.split('=').nth(1).unwrap().parse().unwrap();
+    let tunnels = tunnel_part
+        .splitn(5, ' ')
+        .nth(4)
+        .unwrap()
+        .split(", ")
+        .map(String::from)
+        .collect();
+    Valve {
+        name,
+        flow_rate,
+        tunnels,
+    }
+}
+
+fn parse_input(input: &str) -> Vec<Valve> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+/// Finds all-pairs shortest paths with Floyd-Warshall and keeps only the
+/// valves with a non-zero flow rate
+fn build_network(valves: &[Valve]) -> ValveNetwork {
+    let index: HashMap<&str, usize> = valves
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.name.as_str(), i))
+        .collect();
+    let n = valves.len();
+    let unreachable = u32::MAX / 2;
+
+    let mut dist = vec![vec![unreachable; n]; n];
+    for (i, valve) in valves.iter().enumerate() {
+        dist[i][i] = 0;
+        for tunnel in &valve.tunnels {
+            dist[i][index[tunnel.as_str()]] = 1;
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            for j in 0..n {
+                let through_k = dist[i][k] + dist[k][j];
+                if through_k < dist[i][j] {
+                    dist[i][j] = through_k;
+                }
+            }
+        }
+    }
+
+    let useful: Vec<usize> = (0..n).filter(|&i| valves[i].flow_rate > 0).collect();
+    let start = *index.get("AA").expect("No starting valve AA");
+
+    ValveNetwork {
+        flow_rates: useful.iter().map(|&i| valves[i].flow_rate).collect(),
+        distances: useful
+            .iter()
+            .map(|&i| useful.iter().map(|&j| dist[i][j]).collect())
+            .collect(),
+        start_distances: useful.iter().map(|&j| dist[start][j]).collect(),
+    }
+}
+
+/// For every set of opened valves (as a bitmask), the most pressure that can be
+/// released in `minutes` by opening exactly that set, or `None` if it can't be opened in time
+fn best_pressure_per_set(network: &ValveNetwork, minutes: u32) -> Vec<Option<u32>> {
+    fn visit(
+        network: &ValveNetwork,
+        valve: usize,
+        time_left: u32,
+        opened: usize,
+        pressure: u32,
+        best: &mut [Option<u32>],
+    ) {
+        best[opened] = best[opened].max(Some(pressure));
+        for next in 0..network.flow_rates.len() {
+            // Walking there and opening it takes one extra minute
+            let cost = network.distances[valve][next] + 1;
+            if opened & (1 << next) != 0 || cost >= time_left {
+                continue;
+            }
+            let remaining = time_left - cost;
+            visit(
+                network,
+                next,
+                remaining,
+                opened | (1 << next),
+                pressure + remaining * network.flow_rates[next],
+                best,
+            );
+        }
+    }
+
+    let count = network.flow_rates.len();
+    let mut best = vec![None; 1 << count];
+    best[0] = Some(0);
+    for first in 0..count {
+        let cost = network.start_distances[first] + 1;
+        if cost < minutes {
+            let remaining = minutes - cost;
+            visit(network, first, remaining, 1 << first, remaining * network.flow_rates[first], &mut best);
+        }
+    }
+    best
+}
+
+fn solve_part1(input_text: &str) -> u32 {
+    let network = build_network(&parse_input(input_text));
+    best_pressure_per_set(&network, 30)
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(0)
+}
+
+fn solve_part2(input_text: &str) -> u32 {
+    let network = build_network(&parse_input(input_text));
+    let best = best_pressure_per_set(&network, 26);
+    let all = best.len() - 1;
+
+    // best_within[mask] is the best result using any subset of mask
+    let mut best_within: Vec<u32> = best.iter().map(|b| b.unwrap_or(0)).collect();
+    for bit in 0..network.flow_rates.len() {
+        for mask in 0..best_within.len() {
+            if mask & (1 << bit) != 0 {
+                best_within[mask] = best_within[mask].max(best_within[mask ^ (1 << bit)]);
+            }
+        }
+    }
+
+    // You and the elephant open disjoint sets of valves
+    best.iter()
+        .enumerate()
+        .filter_map(|(mask, pressure)| pressure.map(|p| p + best_within[all & !mask]))
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn solve() {
+    println!("Problem 16");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_16/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 1651);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 1707);
+    }
+}
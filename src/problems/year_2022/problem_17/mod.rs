@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::utils::cycle::HashCycleDetector;
+
+/// Rock shapes as rows from the bottom up, one bit per column with the
+/// leftmost column in bit 6, already placed two units from the left wall
+const ROCKS: [&[u8]; 5] = [
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
+];
+
+const LEFT_WALL: u8 = 0b1000000;
+const RIGHT_WALL: u8 = 0b0000001;
+
+/// How many rows from the top of the tower go into the cycle detection state
+const SKYLINE_ROWS: usize = 64;
+
+#[derive(Debug, Clone, Copy)]
+enum Jet {
+    Left,
+    Right,
+}
+
+fn parse_input(input: &str) -> Vec<Jet> {
+    input
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '<' => Jet::Left,
+            '>' => Jet::Right,
+            _ => panic!("Invalid jet: {}", c),
+        })
+        .collect()
+}
+
+/// The 7-wide chamber, one byte per row
+#[derive(Debug)]
+struct Chamber {
+    rows: Vec<u8>,
+    jets: Vec<Jet>,
+    next_jet: usize,
+    next_rock: usize,
+}
+
+impl Chamber {
+    fn new(jets: Vec<Jet>) -> Self {
+        Chamber {
+            rows: Vec::new(),
+            jets,
+            next_jet: 0,
+            next_rock: 0,
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .any(|(i, &row)| self.rows.get(y + i).is_some_and(|&filled| filled & row != 0))
+    }
+
+    fn push(&self, rock: &[u8], jet: Jet) -> Option<Vec<u8>> {
+        match jet {
+            Jet::Left if rock.iter().all(|&row| row & LEFT_WALL == 0) => {
+                Some(rock.iter().map(|&row| row << 1).collect())
+            }
+            Jet::Right if rock.iter().all(|&row| row & RIGHT_WALL == 0) => {
+                Some(rock.iter().map(|&row| row >> 1).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Drops the next rock until it comes to rest
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.next_rock].to_vec();
+        self.next_rock = (self.next_rock + 1) % ROCKS.len();
+        let mut y = self.height() + 3;
+
+        loop {
+            // Pushed by a jet of gas, if nothing is in the way
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            if let Some(pushed) = self.push(&rock, jet) {
+                if !self.collides(&pushed, y) {
+                    rock = pushed;
+                }
+            }
+
+            // Then falls one unit, unless it lands on the floor or another rock
+            if y == 0 || self.collides(&rock, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for (i, &row) in rock.iter().enumerate() {
+            if y + i >= self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[y + i] |= row;
+        }
+    }
+
+    /// Everything that decides how the next rocks fall: which rock and jet come
+    /// next and the shape of the top of the tower
+    fn state(&self) -> (usize, usize, Vec<u8>) {
+        let skyline = self.rows.iter().rev().take(SKYLINE_ROWS).copied().collect();
+        (self.next_rock, self.next_jet, skyline)
+    }
+}
+
+/// Height of the tower after `rocks` rocks. Once the chamber returns to an
+/// earlier state the height is extrapolated from the repeating cycle.
+fn tower_height(jets: Vec<Jet>, rocks: usize) -> i64 {
+    let mut chamber = Chamber::new(jets);
+    let mut detector = HashCycleDetector::new();
+    let mut heights = vec![0];
+    detector.observe(chamber.state());
+
+    for _ in 0..rocks {
+        chamber.drop_rock();
+        heights.push(chamber.height() as i64);
+        if let Some(cycle) = detector.observe(chamber.state()) {
+            return cycle.extrapolate_metric(&heights, rocks);
+        }
+    }
+
+    heights[rocks]
+}
+
+fn solve_part1(input_text: &str) -> i64 {
+    tower_height(parse_input(input_text), 2022)
+}
+
+fn solve_part2(input_text: &str) -> i64 {
+    tower_height(parse_input(input_text), 1_000_000_000_000)
+}
+
+pub fn solve() {
+    println!("Problem 17");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_17/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 3068);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 1_514_285_714_288);
+    }
+}
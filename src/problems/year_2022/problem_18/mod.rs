@@ -0,0 +1,146 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Cube {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+impl Cube {
+    fn neighbors(&self) -> [Cube; 6] {
+        let Cube { x, y, z } = *self;
+        [
+            Cube { x: x + 1, y, z },
+            Cube { x: x - 1, y, z },
+            Cube { x, y: y + 1, z },
+            Cube { x, y: y - 1, z },
+            Cube { x, y, z: z + 1 },
+            Cube { x, y, z: z - 1 },
+        ]
+    }
+}
+
+fn parse_input(input: &str) -> HashSet<Cube> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let coords: Vec<i32> = line
+                .trim()
+                .split(',')
+                .map(|c| c.parse().unwrap())
+                .collect();
+            if coords.len() != 3 {
+                panic!("Invalid cube: '{}'", line);
+            }
+            Cube {
+                x: coords[0],
+                y: coords[1],
+                z: coords[2],
+            }
+        })
+        .collect()
+}
+
+/// Counts the faces of the droplet that are not touching another cube
+fn surface_area(cubes: &HashSet<Cube>) -> usize {
+    cubes
+        .iter()
+        .flat_map(Cube::neighbors)
+        .filter(|neighbor| !cubes.contains(neighbor))
+        .count()
+}
+
+/// Counts only the faces that steam can reach, by flood-filling the air around
+/// the droplet from a corner of a box one unit larger than it on every side
+fn exterior_surface_area(cubes: &HashSet<Cube>) -> usize {
+    if cubes.is_empty() {
+        return 0;
+    }
+    let min = |axis: fn(&Cube) -> i32| cubes.iter().map(axis).min().unwrap() - 1;
+    let max = |axis: fn(&Cube) -> i32| cubes.iter().map(axis).max().unwrap() + 1;
+    let (min_x, max_x) = (min(|c| c.x), max(|c| c.x));
+    let (min_y, max_y) = (min(|c| c.y), max(|c| c.y));
+    let (min_z, max_z) = (min(|c| c.z), max(|c| c.z));
+    let in_bounds = |c: &Cube| {
+        (min_x..=max_x).contains(&c.x) && (min_y..=max_y).contains(&c.y) && (min_z..=max_z).contains(&c.z)
+    };
+
+    let start = Cube {
+        x: min_x,
+        y: min_y,
+        z: min_z,
+    };
+    let mut outside = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut faces = 0;
+
+    while let Some(air) = queue.pop_front() {
+        for neighbor in air.neighbors() {
+            if !in_bounds(&neighbor) {
+                continue;
+            }
+            if cubes.contains(&neighbor) {
+                // Steam touches this face of the droplet
+                faces += 1;
+            } else if outside.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    faces
+}
+
+fn solve_part1(input_text: &str) -> usize {
+    surface_area(&parse_input(input_text))
+}
+
+fn solve_part2(input_text: &str) -> usize {
+    exterior_surface_area(&parse_input(input_text))
+}
+
+pub fn solve() {
+    println!("Problem 18");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_18/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 64);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 58);
+    }
+}
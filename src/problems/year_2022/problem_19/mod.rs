@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+// Resource and robot indices
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+#[derive(Debug)]
+struct Blueprint {
+    id: u32,
+    // costs[robot][resource] is how much of the resource one robot needs
+    costs: [[u32; 3]; 4],
+    // Building more robots of a kind than any robot costs in that resource never helps
+    max_useful: [u32; 3],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    time_left: u32,
+    resources: [u32; 4],
+    robots: [u32; 4],
+}
+
+fn parse_line(line: &str) -> Blueprint {
+    // Example: Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore.
+    // Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+    let numbers: Vec<u32> = line
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap())
+        .collect();
+    if numbers.len() != 7 {
+        panic!("Invalid blueprint: '{}'", line);
+    }
+
+    let costs = [
+        [numbers[1], 0, 0],
+        [numbers[2], 0, 0],
+        [numbers[3], numbers[4], 0],
+        [numbers[5], 0, numbers[6]],
+    ];
+    let mut max_useful = [0; 3];
+    for cost in &costs {
+        for resource in 0..3 {
+            max_useful[resource] = max_useful[resource].max(cost[resource]);
+        }
+    }
+
+    Blueprint {
+        id: numbers[0],
+        costs,
+        max_useful,
+    }
+}
+
+fn parse_input(input: &str) -> Vec<Blueprint> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+/// Skips ahead to when `robot` can be afforded and builds it, or returns `None`
+/// if that can't happen while there is still time for it to collect anything
+fn build(blueprint: &Blueprint, state: &State, robot: usize) -> Option<State> {
+    let cost = &blueprint.costs[robot];
+    let mut wait = 0;
+    for (resource, &needed) in cost.iter().enumerate() {
+        if state.resources[resource] >= needed {
+            continue;
+        }
+        if state.robots[resource] == 0 {
+            return None;
+        }
+        let missing = needed - state.resources[resource];
+        wait = wait.max(missing.div_ceil(state.robots[resource]));
+    }
+
+    // The robot is ready at the end of the minute it is built in
+    let elapsed = wait + 1;
+    if elapsed >= state.time_left {
+        return None;
+    }
+
+    let mut next = *state;
+    next.time_left -= elapsed;
+    for resource in 0..4 {
+        next.resources[resource] += state.robots[resource] * elapsed;
+    }
+    for (resource, &spent) in cost.iter().enumerate() {
+        next.resources[resource] -= spent;
+    }
+    next.robots[robot] += 1;
+    Some(next)
+}
+
+fn search(blueprint: &Blueprint, state: &State, best: &mut u32) {
+    let t = state.time_left;
+    let geodes = state.resources[GEODE];
+
+    // Geodes collected if no more robots are built
+    *best = (*best).max(geodes + state.robots[GEODE] * t);
+
+    // Even building a geode robot every remaining minute can't beat the best
+    let upper_bound = geodes + state.robots[GEODE] * t + t * t.saturating_sub(1) / 2;
+    if upper_bound <= *best {
+        return;
+    }
+
+    for robot in [GEODE, OBSIDIAN, CLAY, ORE] {
+        if robot != GEODE && state.robots[robot] >= blueprint.max_useful[robot] {
+            continue;
+        }
+        if let Some(next) = build(blueprint, state, robot) {
+            search(blueprint, &next, best);
+        }
+    }
+}
+
+fn max_geodes(blueprint: &Blueprint, minutes: u32) -> u32 {
+    let start = State {
+        time_left: minutes,
+        resources: [0; 4],
+        robots: [1, 0, 0, 0],
+    };
+    let mut best = 0;
+    search(blueprint, &start, &mut best);
+    best
+}
+
+/// Finds the most geodes for every blueprint, one thread per blueprint
+fn max_geodes_all(blueprints: &[Blueprint], minutes: u32) -> Vec<u32> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = blueprints
+            .iter()
+            .map(|blueprint| scope.spawn(move || max_geodes(blueprint, minutes)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Blueprint search panicked"))
+            .collect()
+    })
+}
+
+fn solve_part1(input_text: &str) -> u32 {
+    let blueprints = parse_input(input_text);
+    max_geodes_all(&blueprints, 24)
+        .iter()
+        .zip(&blueprints)
+        .map(|(geodes, blueprint)| geodes * blueprint.id)
+        .sum()
+}
+
+fn solve_part2(input_text: &str) -> u32 {
+    let blueprints = parse_input(input_text);
+    let first_three = &blueprints[..blueprints.len().min(3)];
+    max_geodes_all(first_three, 32).iter().product()
+}
+
+pub fn solve() {
+    println!("Problem 19");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_19/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 33);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 56 * 62);
+    }
+}
@@ -1,44 +1,207 @@
 use std::fs::File;
 use std::io::BufRead;
 
-fn get_shape_for_outcome(opponent: &str, outcome: &str) -> &'static str {
-    match (opponent, outcome) {
-        // To lose
-        ("A", "X") => "Scissors",  // Rock beats Scissors
-        ("B", "X") => "Rock",      // Paper beats Rock
-        ("C", "X") => "Paper",     // Scissors beats Paper
-        
-        // To draw
-        ("A", "Y") => "Rock",
-        ("B", "Y") => "Paper",
-        ("C", "Y") => "Scissors",
-        
-        // To win
-        ("A", "Z") => "Paper",     // Paper beats Rock
-        ("B", "Z") => "Scissors",  // Scissors beats Paper
-        ("C", "Z") => "Rock",      // Rock beats Scissors
-        
-        _ => panic!("Invalid input"),
-    }
-}
-
-pub fn calculate_score(val1: &str, val2: &str) -> i32 {
-    let shape_score = |shape: &str| match shape {
-        "Rock" => 1,
-        "Paper" => 2,
-        "Scissors" => 3,
-        _ => panic!("Invalid shape"),
-    };
+/// Every shape the supported games use. Which of them a game plays and what
+/// beats what is up to its `Rules`.
+// Only the extended games in the tests play the shapes after Scissors
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+    Lizard,
+    Spock,
+    Fire,
+    Sponge,
+    Air,
+    Water,
+}
 
-    let outcome_score = match val2 {
-        "X" => 0,  // Lose
-        "Y" => 3,  // Draw
-        "Z" => 6,  // Win
-        _ => panic!("Invalid outcome"),
-    };
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    fn score(self) -> i32 {
+        match self {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+
+    fn from_letter(letter: &str) -> Option<Outcome> {
+        match letter {
+            "X" => Some(Outcome::Lose),
+            "Y" => Some(Outcome::Draw),
+            "Z" => Some(Outcome::Win),
+            _ => None,
+        }
+    }
+}
 
-    let my_shape = get_shape_for_outcome(val1, val2);
-    shape_score(my_shape) + outcome_score
+/// A cyclic hand game. Shapes score their 1-based position in `shapes`, and
+/// `beats` lists every (winner, loser) pair.
+#[derive(Debug, Clone, Copy)]
+pub struct Rules {
+    pub shapes: &'static [Shape],
+    pub beats: &'static [(Shape, Shape)],
+}
+
+pub const ROCK_PAPER_SCISSORS: Rules = Rules {
+    shapes: &[Shape::Rock, Shape::Paper, Shape::Scissors],
+    beats: &[
+        (Shape::Rock, Shape::Scissors),
+        (Shape::Paper, Shape::Rock),
+        (Shape::Scissors, Shape::Paper),
+    ],
+};
+
+impl Rules {
+    /// Checks that the game is fair: an odd number of shapes, exactly one
+    /// winner between any two different shapes, and every shape beating half
+    /// of the others
+    pub fn validate(&self) -> Result<(), String> {
+        let n = self.shapes.len();
+        if n.is_multiple_of(2) {
+            return Err(format!("A cyclic game needs an odd number of shapes, not {}", n));
+        }
+        for &a in self.shapes {
+            for &b in self.shapes {
+                let wins = self.beats.contains(&(a, b)) as usize + self.beats.contains(&(b, a)) as usize;
+                if a != b && wins != 1 {
+                    return Err(format!("{:?} vs {:?} must have exactly one winner", a, b));
+                }
+                if a == b && wins != 0 {
+                    return Err(format!("{:?} can't beat itself", a));
+                }
+            }
+            let victims = self.beats.iter().filter(|&&(winner, _)| winner == a).count();
+            if victims != (n - 1) / 2 {
+                return Err(format!("{:?} beats {} shapes, expected {}", a, victims, (n - 1) / 2));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> i32 {
+        let index = self
+            .shapes
+            .iter()
+            .position(|&s| s == shape)
+            .unwrap_or_else(|| panic!("{:?} is not part of this game", shape));
+        index as i32 + 1
+    }
+
+    pub fn outcome(&self, mine: Shape, theirs: Shape) -> Outcome {
+        if mine == theirs {
+            Outcome::Draw
+        } else if self.beats.contains(&(mine, theirs)) {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    /// Our score for one round
+    pub fn score(&self, mine: Shape, theirs: Shape) -> i32 {
+        self.shape_score(mine) + self.outcome(mine, theirs).score()
+    }
+
+    /// The highest-scoring shape that gives the wanted outcome
+    pub fn response_for(&self, theirs: Shape, outcome: Outcome) -> Shape {
+        self.shapes
+            .iter()
+            .copied()
+            .filter(|&mine| self.outcome(mine, theirs) == outcome)
+            .max_by_key(|&mine| self.shape_score(mine))
+            .expect("Every outcome is possible in a cyclic game")
+    }
+
+    /// Reads a column letter as a shape, counting from `first`
+    /// (so with `first` 'A', "A" is the first shape, "B" the second, ...)
+    pub fn shape_for_letter(&self, letter: &str, first: char) -> Option<Shape> {
+        let mut chars = letter.chars();
+        let c = chars.next()?;
+        if chars.next().is_some() || c < first {
+            return None;
+        }
+        self.shapes.get((c as u32 - first as u32) as usize).copied()
+    }
+}
+
+/// Part 1: the second column is the shape to play
+pub fn calculate_score(rules: &Rules, val1: &str, val2: &str) -> i32 {
+    let theirs = rules.shape_for_letter(val1, 'A').expect("Invalid opponent shape");
+    let mine = rules.shape_for_letter(val2, 'X').expect("Invalid shape");
+    rules.score(mine, theirs)
+}
+
+/// Part 2: the second column is the outcome to aim for
+pub fn calculate_score_for_outcome(rules: &Rules, val1: &str, val2: &str) -> i32 {
+    let theirs = rules.shape_for_letter(val1, 'A').expect("Invalid opponent shape");
+    let outcome = Outcome::from_letter(val2).expect("Invalid outcome");
+    rules.score(rules.response_for(theirs, outcome), theirs)
+}
+
+/// The best response to each opponent shape and the total score it earns
+pub fn best_responses(rules: &Rules, opponents: &[Shape]) -> (i32, Vec<Shape>) {
+    let responses: Vec<Shape> = opponents
+        .iter()
+        .map(|&theirs| {
+            rules
+                .shapes
+                .iter()
+                .copied()
+                .max_by_key(|&mine| rules.score(mine, theirs))
+                .expect("A game needs at least one shape")
+        })
+        .collect();
+    let total = responses
+        .iter()
+        .zip(opponents)
+        .map(|(&mine, &theirs)| rules.score(mine, theirs))
+        .sum();
+    (total, responses)
+}
+
+/// Every ordering of the given shapes
+fn permutations(shapes: &[Shape]) -> Vec<Vec<Shape>> {
+    if shapes.is_empty() {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, &first) in shapes.iter().enumerate() {
+        let mut rest = shapes.to_vec();
+        rest.remove(i);
+        for mut tail in permutations(&rest) {
+            tail.insert(0, first);
+            result.push(tail);
+        }
+    }
+    result
+}
+
+/// Scores the guide under every way of reading the response column as shapes
+/// (`mapping[i]` is the shape for the i-th letter from 'X'), best first.
+/// `guide` holds the opponent shape and the response column index of each round.
+pub fn rank_mappings(rules: &Rules, guide: &[(Shape, usize)]) -> Vec<(Vec<Shape>, i32)> {
+    let mut ranking: Vec<(Vec<Shape>, i32)> = permutations(rules.shapes)
+        .into_iter()
+        .map(|mapping| {
+            let score = guide
+                .iter()
+                .map(|&(theirs, column)| rules.score(mapping[column], theirs))
+                .sum();
+            (mapping, score)
+        })
+        .collect();
+    ranking.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    ranking
 }
 
 pub fn solve() {
@@ -46,14 +209,202 @@ pub fn solve() {
     let file = File::open("src/problems/year_2022/problem_2/input.txt").unwrap();
     let reader = std::io::BufReader::new(file);
 
-    let mut player_score = 0;
+    let rules = ROCK_PAPER_SCISSORS;
+    rules.validate().expect("Invalid rules");
+
+    let mut part1_score = 0;
+    let mut part2_score = 0;
+    let mut guide = Vec::new();
 
     for line in reader.lines() {
         let line = line.unwrap();
         let choices: Vec<&str> = line.split_whitespace().collect();
-        let score = calculate_score(choices[0], choices[1]);
-        player_score += score;
+        if choices.len() != 2 {
+            continue;
+        }
+        part1_score += calculate_score(&rules, choices[0], choices[1]);
+        part2_score += calculate_score_for_outcome(&rules, choices[0], choices[1]);
+
+        let theirs = rules.shape_for_letter(choices[0], 'A').expect("Invalid opponent shape");
+        let column = rules
+            .shape_for_letter(choices[1], 'X')
+            .and_then(|shape| rules.shapes.iter().position(|&s| s == shape))
+            .expect("Invalid shape");
+        guide.push((theirs, column));
+    }
+
+    println!("Part 1: Player score: {}", part1_score);
+    println!("Part 2: Player score: {}", part2_score);
+
+    let opponents: Vec<Shape> = guide.iter().map(|&(theirs, _)| theirs).collect();
+    let (best_score, responses) = best_responses(&rules, &opponents);
+    let opening: Vec<String> = responses.iter().take(5).map(|s| format!("{:?}", s)).collect();
+    println!(
+        "Best possible score: {} (playing {}, ...)",
+        best_score,
+        opening.join(", ")
+    );
+
+    println!("Scores for each reading of X/Y/Z:");
+    for (mapping, score) in rank_mappings(&rules, &guide) {
+        let mapping: Vec<String> = ["X", "Y", "Z"]
+            .iter()
+            .zip(&mapping)
+            .map(|(letter, shape)| format!("{}={:?}", letter, shape))
+            .collect();
+        println!("  {}: {}", mapping.join(" "), score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROCK_PAPER_SCISSORS_LIZARD_SPOCK: Rules = Rules {
+        shapes: &[Shape::Rock, Shape::Paper, Shape::Scissors, Shape::Lizard, Shape::Spock],
+        beats: &[
+            (Shape::Rock, Shape::Scissors),
+            (Shape::Rock, Shape::Lizard),
+            (Shape::Paper, Shape::Rock),
+            (Shape::Paper, Shape::Spock),
+            (Shape::Scissors, Shape::Paper),
+            (Shape::Scissors, Shape::Lizard),
+            (Shape::Lizard, Shape::Paper),
+            (Shape::Lizard, Shape::Spock),
+            (Shape::Spock, Shape::Rock),
+            (Shape::Spock, Shape::Scissors),
+        ],
+    };
+
+    // Every shape beats the three that follow it round the circle
+    const ROCK_PAPER_SCISSORS_7: Rules = Rules {
+        shapes: &[
+            Shape::Rock,
+            Shape::Fire,
+            Shape::Scissors,
+            Shape::Sponge,
+            Shape::Paper,
+            Shape::Air,
+            Shape::Water,
+        ],
+        beats: &[
+            (Shape::Rock, Shape::Fire),
+            (Shape::Rock, Shape::Scissors),
+            (Shape::Rock, Shape::Sponge),
+            (Shape::Fire, Shape::Scissors),
+            (Shape::Fire, Shape::Sponge),
+            (Shape::Fire, Shape::Paper),
+            (Shape::Scissors, Shape::Sponge),
+            (Shape::Scissors, Shape::Paper),
+            (Shape::Scissors, Shape::Air),
+            (Shape::Sponge, Shape::Paper),
+            (Shape::Sponge, Shape::Air),
+            (Shape::Sponge, Shape::Water),
+            (Shape::Paper, Shape::Air),
+            (Shape::Paper, Shape::Water),
+            (Shape::Paper, Shape::Rock),
+            (Shape::Air, Shape::Water),
+            (Shape::Air, Shape::Rock),
+            (Shape::Air, Shape::Fire),
+            (Shape::Water, Shape::Rock),
+            (Shape::Water, Shape::Fire),
+            (Shape::Water, Shape::Scissors),
+        ],
+    };
+
+    #[test]
+    fn scores_example() {
+        let rounds = [("A", "Y"), ("B", "X"), ("C", "Z")];
+        let rules = ROCK_PAPER_SCISSORS;
+        let part1: i32 = rounds.iter().map(|&(a, b)| calculate_score(&rules, a, b)).sum();
+        let part2: i32 = rounds
+            .iter()
+            .map(|&(a, b)| calculate_score_for_outcome(&rules, a, b))
+            .sum();
+        assert_eq!(part1, 15);
+        assert_eq!(part2, 12);
+    }
+
+    #[test]
+    fn ranks_example_mappings() {
+        // A Y, B X, C Z: the response columns are Y, X and Z
+        let guide = [(Shape::Rock, 1), (Shape::Paper, 0), (Shape::Scissors, 2)];
+        let ranking = rank_mappings(&ROCK_PAPER_SCISSORS, &guide);
+        assert_eq!(
+            ranking,
+            vec![
+                (vec![Shape::Scissors, Shape::Paper, Shape::Rock], 24),
+                (vec![Shape::Rock, Shape::Paper, Shape::Scissors], 15),
+                (vec![Shape::Paper, Shape::Rock, Shape::Scissors], 15),
+                (vec![Shape::Paper, Shape::Scissors, Shape::Rock], 15),
+                (vec![Shape::Scissors, Shape::Rock, Shape::Paper], 15),
+                (vec![Shape::Rock, Shape::Scissors, Shape::Paper], 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_every_permutation() {
+        assert_eq!(permutations(&[]), vec![Vec::<Shape>::new()]);
+        let shapes = ROCK_PAPER_SCISSORS_LIZARD_SPOCK.shapes;
+        let mut all = permutations(&shapes[..4]);
+        assert_eq!(all.len(), 24);
+        all.sort_by_key(|p| format!("{:?}", p));
+        all.dedup();
+        assert_eq!(all.len(), 24);
     }
 
-    println!("Player score: {}", player_score);
-}
\ No newline at end of file
+    #[test]
+    fn finds_best_responses_to_example() {
+        let opponents = [Shape::Rock, Shape::Paper, Shape::Scissors];
+        let (total, responses) = best_responses(&ROCK_PAPER_SCISSORS, &opponents);
+        assert_eq!(responses, vec![Shape::Paper, Shape::Scissors, Shape::Rock]);
+        assert_eq!(total, 8 + 9 + 7);
+        assert_eq!(best_responses(&ROCK_PAPER_SCISSORS, &[]), (0, Vec::new()));
+    }
+
+    #[test]
+    fn scores_rock_paper_scissors_lizard_spock() {
+        let rules = ROCK_PAPER_SCISSORS_LIZARD_SPOCK;
+        assert_eq!(rules.validate(), Ok(()));
+        assert_eq!(rules.score(Shape::Spock, Shape::Rock), 5 + 6);
+        assert_eq!(rules.score(Shape::Lizard, Shape::Spock), 4 + 6);
+        assert_eq!(rules.score(Shape::Rock, Shape::Spock), 1);
+        assert_eq!(rules.score(Shape::Lizard, Shape::Lizard), 4 + 3);
+        assert_eq!(rules.response_for(Shape::Rock, Outcome::Win), Shape::Spock);
+        assert_eq!(rules.response_for(Shape::Rock, Outcome::Lose), Shape::Lizard);
+        assert_eq!(rules.response_for(Shape::Rock, Outcome::Draw), Shape::Rock);
+        assert_eq!(rules.shape_for_letter("E", 'A'), Some(Shape::Spock));
+        assert_eq!(rules.shape_for_letter("F", 'A'), None);
+    }
+
+    #[test]
+    fn plays_a_seven_shape_game() {
+        let rules = ROCK_PAPER_SCISSORS_7;
+        assert_eq!(rules.validate(), Ok(()));
+        assert_eq!(rules.score(Shape::Water, Shape::Scissors), 7 + 6);
+        assert_eq!(rules.response_for(Shape::Rock, Outcome::Win), Shape::Water);
+        assert_eq!(rules.response_for(Shape::Rock, Outcome::Lose), Shape::Sponge);
+        let (total, responses) = best_responses(&rules, &[Shape::Rock, Shape::Water]);
+        assert_eq!(responses, vec![Shape::Water, Shape::Air]);
+        assert_eq!(total, 7 + 6 + 6 + 6);
+    }
+
+    #[test]
+    fn rejects_unfair_games() {
+        let even = Rules {
+            shapes: &[Shape::Rock, Shape::Paper],
+            beats: &[(Shape::Paper, Shape::Rock)],
+        };
+        assert!(even.validate().is_err());
+        let one_sided = Rules {
+            shapes: &[Shape::Rock, Shape::Paper, Shape::Scissors],
+            beats: &[
+                (Shape::Rock, Shape::Scissors),
+                (Shape::Rock, Shape::Paper),
+                (Shape::Scissors, Shape::Paper),
+            ],
+        };
+        assert!(one_sided.validate().is_err());
+    }
+}
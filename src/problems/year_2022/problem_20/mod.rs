@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::PathBuf;
+
+const DECRYPTION_KEY: i64 = 811_589_153;
+
+fn parse_input(input: &str) -> Vec<i64> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.trim().parse().unwrap())
+        .collect()
+}
+
+/// Mixes the numbers `rounds` times. Values can repeat, so the sequence holds
+/// indices into `numbers` rather than the values themselves.
+fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    let len = numbers.len();
+    // With fewer than two numbers there is nowhere to move to
+    if len <= 1 {
+        return numbers.to_vec();
+    }
+    let mut order: Vec<usize> = (0..len).collect();
+
+    for _ in 0..rounds {
+        for (index, &value) in numbers.iter().enumerate() {
+            let position = order.iter().position(|&i| i == index).unwrap();
+            order.remove(position);
+            // Moving a number around the whole circle (less itself) lands it back in place
+            let new_position = (position as i64 + value).rem_euclid(len as i64 - 1) as usize;
+            order.insert(new_position, index);
+        }
+    }
+
+    order.into_iter().map(|i| numbers[i]).collect()
+}
+
+/// Sums the 1000th, 2000th and 3000th numbers after the 0
+fn grove_coordinates(mixed: &[i64]) -> i64 {
+    let zero = mixed
+        .iter()
+        .position(|&n| n == 0)
+        .expect("No 0 in the sequence");
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| mixed[(zero + offset) % mixed.len()])
+        .sum()
+}
+
+fn solve_part1(input_text: &str) -> i64 {
+    let numbers = parse_input(input_text);
+    grove_coordinates(&mix(&numbers, 1))
+}
+
+fn solve_part2(input_text: &str) -> i64 {
+    let numbers: Vec<i64> = parse_input(input_text)
+        .into_iter()
+        .map(|n| n * DECRYPTION_KEY)
+        .collect();
+    grove_coordinates(&mix(&numbers, 10))
+}
+
+pub fn solve() {
+    println!("Problem 20");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_20/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1
+2
+-3
+3
+-2
+0
+4
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 3);
+    }
+
+    #[test]
+    fn single_number_stays_put() {
+        assert_eq!(mix(&[0], 1), vec![0]);
+        assert_eq!(solve_part1("0\n"), 0);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 1_623_178_306);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug)]
+enum Job {
+    Number(i64),
+    Operation(String, Operation, String),
+}
+
+fn parse_line(line: &str) -> (String, Job) {
+    // Examples: "root: pppw + sjmn" and "dbpl: 5"
+    let (name, job) = line
+        .split_once(": ")
+        .unwrap_or_else(|| panic!("Invalid line format: '{}'", line));
+    let parts: Vec<&str> = job.split_whitespace().collect();
+    let job = match parts[..] {
+        [number] => Job::Number(number.parse().unwrap()),
+        [left, op, right] => {
+            let op = match op {
+                "+" => Operation::Add,
+                "-" => Operation::Subtract,
+                "*" => Operation::Multiply,
+                "/" => Operation::Divide,
+                _ => panic!("Unknown operation: {}", op),
+            };
+            Job::Operation(left.to_string(), op, right.to_string())
+        }
+        _ => panic!("Invalid job: '{}'", job),
+    };
+    (name.to_string(), job)
+}
+
+fn parse_input(input: &str) -> HashMap<String, Job> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+fn apply(op: Operation, left: i64, right: i64) -> i64 {
+    match op {
+        Operation::Add => left + right,
+        Operation::Subtract => left - right,
+        Operation::Multiply => left * right,
+        Operation::Divide => left / right,
+    }
+}
+
+fn operands<'a>(jobs: &'a HashMap<String, Job>, name: &str) -> (&'a str, Operation, &'a str) {
+    match &jobs[name] {
+        Job::Operation(left, op, right) => (left, *op, right),
+        Job::Number(_) => panic!("Monkey {} only yells a number", name),
+    }
+}
+
+fn evaluate(jobs: &HashMap<String, Job>, name: &str) -> i64 {
+    match &jobs[name] {
+        Job::Number(n) => *n,
+        Job::Operation(left, op, right) => apply(*op, evaluate(jobs, left), evaluate(jobs, right)),
+    }
+}
+
+fn depends_on_human(jobs: &HashMap<String, Job>, name: &str) -> bool {
+    name == HUMAN
+        || match &jobs[name] {
+            Job::Number(_) => false,
+            Job::Operation(left, _, right) => {
+                depends_on_human(jobs, left) || depends_on_human(jobs, right)
+            }
+        }
+}
+
+/// Finds the number the human must yell for monkey `name` to yell `target`,
+/// undoing one operation at a time along the path down to the human
+fn solve_for_human(jobs: &HashMap<String, Job>, name: &str, target: i64) -> i64 {
+    if name == HUMAN {
+        return target;
+    }
+
+    let (left, op, right) = operands(jobs, name);
+    if depends_on_human(jobs, left) {
+        // target = unknown op right
+        let right = evaluate(jobs, right);
+        let needed = match op {
+            Operation::Add => target - right,
+            Operation::Subtract => target + right,
+            Operation::Multiply => target / right,
+            Operation::Divide => target * right,
+        };
+        solve_for_human(jobs, left, needed)
+    } else {
+        // target = left op unknown
+        let left = evaluate(jobs, left);
+        let needed = match op {
+            Operation::Add => target - left,
+            Operation::Subtract => left - target,
+            Operation::Multiply => target / left,
+            Operation::Divide => left / target,
+        };
+        solve_for_human(jobs, right, needed)
+    }
+}
+
+fn solve_part1(input_text: &str) -> i64 {
+    let jobs = parse_input(input_text);
+    evaluate(&jobs, ROOT)
+}
+
+fn solve_part2(input_text: &str) -> i64 {
+    let jobs = parse_input(input_text);
+
+    // Root checks its two numbers are equal, so the side with the human must
+    // match the value of the other side
+    let (left, _, right) = operands(&jobs, ROOT);
+    let (unknown, known) = if depends_on_human(&jobs, left) {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    solve_for_human(&jobs, unknown, evaluate(&jobs, known))
+}
+
+pub fn solve() {
+    println!("Problem 21");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_21/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 152);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 301);
+    }
+}
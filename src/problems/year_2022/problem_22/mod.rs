@@ -0,0 +1,412 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+
+// Facings, in the order used for the password: right, down, left, up
+const RIGHT: usize = 0;
+const DOWN: usize = 1;
+const LEFT: usize = 2;
+const UP: usize = 3;
+const DELTAS: [(i64, i64); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+#[derive(Debug, Clone, Copy)]
+enum Instruction {
+    Forward(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct State {
+    row: usize,
+    col: usize,
+    facing: usize,
+}
+
+impl State {
+    fn password(&self) -> usize {
+        1000 * (self.row + 1) + 4 * (self.col + 1) + self.facing
+    }
+}
+
+#[derive(Debug)]
+struct Board {
+    tiles: Vec<Vec<u8>>,
+}
+
+impl Board {
+    /// The tile at a position, with anything off the map as b' '
+    fn tile(&self, row: i64, col: i64) -> u8 {
+        if row < 0 || col < 0 {
+            return b' ';
+        }
+        self.tiles
+            .get(row as usize)
+            .and_then(|r| r.get(col as usize))
+            .copied()
+            .unwrap_or(b' ')
+    }
+
+    fn start(&self) -> State {
+        let col = self.tiles[0]
+            .iter()
+            .position(|&t| t == b'.')
+            .expect("No open tile on the top row");
+        State { row: 0, col, facing: RIGHT }
+    }
+}
+
+fn parse_input(input: &str) -> (Board, Vec<Instruction>) {
+    let (map, path) = input
+        .split_once("\n\n")
+        .expect("Missing blank line between the map and the path");
+
+    let width = map.lines().map(str::len).max().unwrap_or(0);
+    let tiles = map
+        .lines()
+        .map(|line| {
+            let mut row = line.as_bytes().to_vec();
+            row.resize(width, b' ');
+            row
+        })
+        .collect();
+
+    let mut instructions = Vec::new();
+    let mut number = String::new();
+    for c in path.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if !number.is_empty() {
+            instructions.push(Instruction::Forward(number.parse().unwrap()));
+            number.clear();
+        }
+        match c {
+            'L' => instructions.push(Instruction::TurnLeft),
+            'R' => instructions.push(Instruction::TurnRight),
+            _ => panic!("Invalid path character: {}", c),
+        }
+    }
+    if !number.is_empty() {
+        instructions.push(Instruction::Forward(number.parse().unwrap()));
+    }
+
+    (Board { tiles }, instructions)
+}
+
+/// Follows the path, calling `wrap` to find where each step off the map lands
+fn walk(board: &Board, path: &[Instruction], wrap: impl Fn(&State) -> State) -> State {
+    let mut state = board.start();
+    for instruction in path {
+        match *instruction {
+            Instruction::TurnLeft => state.facing = (state.facing + 3) % 4,
+            Instruction::TurnRight => state.facing = (state.facing + 1) % 4,
+            Instruction::Forward(steps) => {
+                for _ in 0..steps {
+                    let (dr, dc) = DELTAS[state.facing];
+                    let (row, col) = (state.row as i64 + dr, state.col as i64 + dc);
+                    let next = if board.tile(row, col) == b' ' {
+                        wrap(&state)
+                    } else {
+                        State {
+                            row: row as usize,
+                            col: col as usize,
+                            facing: state.facing,
+                        }
+                    };
+                    if board.tiles[next.row][next.col] == b'#' {
+                        break;
+                    }
+                    state = next;
+                }
+            }
+        }
+    }
+    state
+}
+
+/// Wraps around to the far side of the map in the same row or column
+fn flat_wrap(board: &Board, state: &State) -> State {
+    let (dr, dc) = DELTAS[state.facing];
+    let (mut row, mut col) = (state.row as i64, state.col as i64);
+    while board.tile(row - dr, col - dc) != b' ' {
+        row -= dr;
+        col -= dc;
+    }
+    State {
+        row: row as usize,
+        col: col as usize,
+        facing: state.facing,
+    }
+}
+
+type Vector = [i32; 3];
+
+fn neg(v: Vector) -> Vector {
+    [-v[0], -v[1], -v[2]]
+}
+
+/// One face of the cube: where it sits on the map, and which way its outward
+/// normal and the map's right and down directions point once folded
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    row: usize,
+    col: usize,
+    normal: Vector,
+    right: Vector,
+    down: Vector,
+}
+
+impl Face {
+    /// The 3D direction of a facing on this face
+    fn direction(&self, facing: usize) -> Vector {
+        match facing {
+            RIGHT => self.right,
+            DOWN => self.down,
+            LEFT => neg(self.right),
+            _ => neg(self.down),
+        }
+    }
+
+    /// The 3D direction in which offsets grow along the edge crossed by `facing`
+    fn edge_axis(&self, facing: usize) -> Vector {
+        if facing == RIGHT || facing == LEFT {
+            self.down
+        } else {
+            self.right
+        }
+    }
+}
+
+/// The map folded into a cube, with the folding worked out from the layout of the net
+#[derive(Debug)]
+struct Cube {
+    size: usize,
+    faces: Vec<Face>,
+    // Face index by (row, col) of the face in units of the face size
+    face_at: HashMap<(usize, usize), usize>,
+}
+
+impl Cube {
+    fn fold(board: &Board) -> Self {
+        let tile_count = board.tiles.iter().flatten().filter(|&&t| t != b' ').count();
+        let size = ((tile_count / 6) as f64).sqrt().round() as usize;
+        if size == 0 || size * size * 6 != tile_count {
+            panic!("The map can't fold into a cube ({} tiles)", tile_count);
+        }
+
+        let face_rows = board.tiles.len() / size;
+        let face_cols = board.tiles[0].len() / size;
+        let is_face = |fr: usize, fc: usize| board.tiles[fr * size][fc * size] != b' ';
+        let first = (0..face_rows)
+            .flat_map(|fr| (0..face_cols).map(move |fc| (fr, fc)))
+            .find(|&(fr, fc)| is_face(fr, fc))
+            .unwrap();
+
+        // Walk the net from the first face, folding each neighbor over the shared edge
+        let mut faces = vec![Face {
+            row: first.0 * size,
+            col: first.1 * size,
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        }];
+        let mut face_at = HashMap::from([(first, 0)]);
+        let mut queue = VecDeque::from([first]);
+
+        while let Some((fr, fc)) = queue.pop_front() {
+            let face = faces[face_at[&(fr, fc)]];
+            let neighbors = [
+                (fr as i64, fc as i64 + 1, RIGHT),
+                (fr as i64 + 1, fc as i64, DOWN),
+                (fr as i64, fc as i64 - 1, LEFT),
+                (fr as i64 - 1, fc as i64, UP),
+            ];
+            for (nr, nc, facing) in neighbors {
+                if nr < 0 || nc < 0 || nr as usize >= face_rows || nc as usize >= face_cols {
+                    continue;
+                }
+                let key = (nr as usize, nc as usize);
+                if !is_face(key.0, key.1) || face_at.contains_key(&key) {
+                    continue;
+                }
+
+                // The neighbor's normal points where we were heading, and the
+                // direction we were heading in now points into the cube
+                let toward = face.direction(facing);
+                let mut folded = Face {
+                    row: key.0 * size,
+                    col: key.1 * size,
+                    normal: toward,
+                    right: face.right,
+                    down: face.down,
+                };
+                match facing {
+                    RIGHT => folded.right = neg(face.normal),
+                    LEFT => folded.right = face.normal,
+                    DOWN => folded.down = neg(face.normal),
+                    _ => folded.down = face.normal,
+                }
+
+                face_at.insert(key, faces.len());
+                faces.push(folded);
+                queue.push_back(key);
+            }
+        }
+
+        if faces.len() != 6 {
+            panic!("The map has {} connected faces, not 6", faces.len());
+        }
+        Cube { size, faces, face_at }
+    }
+
+    /// Steps over the edge of the current face onto the adjacent face of the cube
+    fn wrap(&self, state: &State) -> State {
+        let n = self.size;
+        let from = self.faces[self.face_at[&(state.row / n, state.col / n)]];
+
+        // The face we walk onto is the one facing the way we were walking,
+        // and on it we head away from the face we left
+        let heading = from.direction(state.facing);
+        let to = *self
+            .faces
+            .iter()
+            .find(|f| f.normal == heading)
+            .expect("Cube is missing a face");
+        let facing = (0..4)
+            .find(|&f| to.direction(f) == neg(from.normal))
+            .unwrap();
+
+        // Both edges are the same edge of the cube; flip the offset if they run opposite ways
+        let offset = match state.facing {
+            RIGHT | LEFT => state.row % n,
+            _ => state.col % n,
+        };
+        let offset = if from.edge_axis(state.facing) == to.edge_axis(facing) {
+            offset
+        } else {
+            n - 1 - offset
+        };
+
+        let (row, col) = match facing {
+            RIGHT => (offset, 0),
+            LEFT => (offset, n - 1),
+            DOWN => (0, offset),
+            _ => (n - 1, offset),
+        };
+        State {
+            row: to.row + row,
+            col: to.col + col,
+            facing,
+        }
+    }
+}
+
+fn solve_part1(input_text: &str) -> usize {
+    let (board, path) = parse_input(input_text);
+    walk(&board, &path, |state| flat_wrap(&board, state)).password()
+}
+
+fn solve_part2(input_text: &str) -> usize {
+    let (board, path) = parse_input(input_text);
+    let cube = Cube::fold(&board);
+    walk(&board, &path, |state| cube.wrap(state)).password()
+}
+
+pub fn solve() {
+    println!("Problem 22");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_22/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 6032);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 5031);
+    }
+
+    /// A wall-free board shaped like `net`, where each '#' is a face of `size` x `size` tiles
+    fn open_board(net: &[&str], size: usize) -> Board {
+        let width = net.iter().map(|row| row.len()).max().unwrap() * size;
+        let tiles = net
+            .iter()
+            .flat_map(|row| {
+                let mut tiles: Vec<u8> = row
+                    .bytes()
+                    .flat_map(|face| vec![if face == b'#' { b'.' } else { b' ' }; size])
+                    .collect();
+                tiles.resize(width, b' ');
+                vec![tiles; size]
+            })
+            .collect();
+        Board { tiles }
+    }
+
+    /// Walking 4 faces in a straight line goes once around the cube, back to the start
+    fn assert_loops_around(net: &[&str], size: usize) {
+        let board = open_board(net, size);
+        let cube = Cube::fold(&board);
+        for row in 0..board.tiles.len() {
+            for col in 0..board.tiles[row].len() {
+                if board.tiles[row][col] == b' ' {
+                    continue;
+                }
+                for facing in 0..4 {
+                    let start = State { row, col, facing };
+                    let mut state = start;
+                    for _ in 0..4 * size {
+                        let (dr, dc) = DELTAS[state.facing];
+                        let (r, c) = (state.row as i64 + dr, state.col as i64 + dc);
+                        state = if board.tile(r, c) == b' ' {
+                            cube.wrap(&state)
+                        } else {
+                            State { row: r as usize, col: c as usize, facing: state.facing }
+                        };
+                    }
+                    assert_eq!(state, start, "net {:?}", net);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn folds_example_net() {
+        assert_loops_around(&["..#.", "###.", "..##"], 4);
+    }
+
+    #[test]
+    fn folds_input_net() {
+        assert_loops_around(&[".##", ".#.", "##.", "#.."], 5);
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+type Elf = (i64, i64); // (row, col)
+
+const NORTH: Elf = (-1, 0);
+const SOUTH: Elf = (1, 0);
+const WEST: Elf = (0, -1);
+const EAST: Elf = (0, 1);
+
+// The direction each proposal moves in, and the three tiles it must find empty
+const PROPOSALS: [(Elf, [Elf; 3]); 4] = [
+    (NORTH, [(-1, -1), (-1, 0), (-1, 1)]),
+    (SOUTH, [(1, -1), (1, 0), (1, 1)]),
+    (WEST, [(-1, -1), (0, -1), (1, -1)]),
+    (EAST, [(-1, 1), (0, 1), (1, 1)]),
+];
+
+const NEIGHBORS: [Elf; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+fn parse_input(input: &str) -> HashSet<Elf> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(col, _)| (row as i64, col as i64))
+        })
+        .collect()
+}
+
+fn offset(elf: Elf, delta: Elf) -> Elf {
+    (elf.0 + delta.0, elf.1 + delta.1)
+}
+
+/// Plays one round, with proposals considered starting from `PROPOSALS[first]`.
+/// Returns the number of elves that moved.
+fn play_round(elves: &mut HashSet<Elf>, first: usize) -> usize {
+    // Destination of each elf that proposes a move, and how many elves want each tile
+    let mut proposals: HashMap<Elf, Elf> = HashMap::new();
+    let mut wanted: HashMap<Elf, usize> = HashMap::new();
+
+    for &elf in elves.iter() {
+        if NEIGHBORS.iter().all(|&d| !elves.contains(&offset(elf, d))) {
+            continue;
+        }
+        let proposal = (0..4)
+            .map(|i| PROPOSALS[(first + i) % 4])
+            .find(|(_, checks)| checks.iter().all(|&d| !elves.contains(&offset(elf, d))));
+        if let Some((direction, _)) = proposal {
+            let target = offset(elf, direction);
+            proposals.insert(elf, target);
+            *wanted.entry(target).or_insert(0) += 1;
+        }
+    }
+
+    let mut moved = 0;
+    for (elf, target) in proposals {
+        if wanted[&target] == 1 {
+            elves.remove(&elf);
+            elves.insert(target);
+            moved += 1;
+        }
+    }
+    moved
+}
+
+fn empty_tiles(elves: &HashSet<Elf>) -> i64 {
+    let min_row = elves.iter().map(|e| e.0).min().unwrap_or(0);
+    let max_row = elves.iter().map(|e| e.0).max().unwrap_or(-1);
+    let min_col = elves.iter().map(|e| e.1).min().unwrap_or(0);
+    let max_col = elves.iter().map(|e| e.1).max().unwrap_or(-1);
+    (max_row - min_row + 1) * (max_col - min_col + 1) - elves.len() as i64
+}
+
+fn solve_part1(input_text: &str) -> i64 {
+    let mut elves = parse_input(input_text);
+    for round in 0..10 {
+        play_round(&mut elves, round % 4);
+    }
+    empty_tiles(&elves)
+}
+
+fn solve_part2(input_text: &str) -> usize {
+    let mut elves = parse_input(input_text);
+    let mut round = 0;
+    while play_round(&mut elves, round % 4) > 0 {
+        round += 1;
+    }
+    round + 1
+}
+
+pub fn solve() {
+    println!("Problem 23");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_23/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "..............
+..............
+.......#......
+.....###.#....
+...#...#.#....
+....#...##....
+...#.###......
+...##.#.##....
+....#..#......
+..............
+..............
+..............
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 110);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 20);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::utils::math;
+
+type Position = (i64, i64); // (row, col) inside the walls
+
+/// The valley inside the walls. Blizzards wrap around, so where each one is
+/// depends only on the time, and the whole valley repeats every `period` minutes.
+#[derive(Debug)]
+struct Valley {
+    width: i64,
+    height: i64,
+    // Starting blizzard layout, one char per tile ('.' for clear)
+    blizzards: Vec<Vec<char>>,
+    period: usize,
+    start: Position,
+    goal: Position,
+}
+
+impl Valley {
+    fn parse(input: &str) -> Self {
+        let lines: Vec<&str> = input.lines().filter(|line| !line.trim().is_empty()).collect();
+        if lines.len() < 3 {
+            panic!("Valley is too small");
+        }
+        let blizzards: Vec<Vec<char>> = lines[1..lines.len() - 1]
+            .iter()
+            .map(|line| {
+                let chars: Vec<char> = line.trim().chars().collect();
+                chars[1..chars.len() - 1].to_vec()
+            })
+            .collect();
+        let height = blizzards.len() as i64;
+        let width = blizzards[0].len() as i64;
+
+        let gap = |line: &str| line.trim().find('.').expect("Wall has no gap") as i64 - 1;
+        Valley {
+            width,
+            height,
+            blizzards,
+            period: math::lcm(width, height) as usize,
+            start: (-1, gap(lines[0])),
+            goal: (height, gap(lines[lines.len() - 1])),
+        }
+    }
+
+    fn blizzard_at(&self, row: i64, col: i64, direction: char) -> bool {
+        self.blizzards[row as usize][col as usize] == direction
+    }
+
+    /// Whether a position is inside the valley and clear of blizzards at `time`
+    fn is_clear(&self, (row, col): Position, time: usize) -> bool {
+        if (row, col) == self.start || (row, col) == self.goal {
+            return true;
+        }
+        if row < 0 || col < 0 || row >= self.height || col >= self.width {
+            return false;
+        }
+        // Look back along each axis for a blizzard that would have reached us by now
+        let t = time as i64;
+        !(self.blizzard_at(row, (col - t).rem_euclid(self.width), '>')
+            || self.blizzard_at(row, (col + t).rem_euclid(self.width), '<')
+            || self.blizzard_at((row - t).rem_euclid(self.height), col, 'v')
+            || self.blizzard_at((row + t).rem_euclid(self.height), col, '^'))
+    }
+
+    /// The earliest time `to` can be reached when leaving `from` at `start_time`,
+    /// searching over (position, time) with time taken modulo the period
+    fn earliest_arrival(&self, from: Position, to: Position, start_time: usize) -> Option<usize> {
+        let mut queue = VecDeque::from([(from, start_time)]);
+        let mut seen = HashSet::from([(from, start_time % self.period)]);
+
+        while let Some(((row, col), time)) = queue.pop_front() {
+            if (row, col) == to {
+                return Some(time);
+            }
+            let moves = [(row, col), (row + 1, col), (row - 1, col), (row, col + 1), (row, col - 1)];
+            for next in moves {
+                if self.is_clear(next, time + 1) && seen.insert((next, (time + 1) % self.period)) {
+                    queue.push_back((next, time + 1));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn solve_part1(input_text: &str) -> usize {
+    let valley = Valley::parse(input_text);
+    valley
+        .earliest_arrival(valley.start, valley.goal, 0)
+        .expect("No way through the valley")
+}
+
+fn solve_part2(input_text: &str) -> usize {
+    let valley = Valley::parse(input_text);
+    // There, back for the snacks, and there again
+    let legs = [
+        (valley.start, valley.goal),
+        (valley.goal, valley.start),
+        (valley.start, valley.goal),
+    ];
+    legs.iter().fold(0, |time, &(from, to)| {
+        valley
+            .earliest_arrival(from, to, time)
+            .expect("No way through the valley")
+    })
+}
+
+pub fn solve() {
+    println!("Problem 24");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_24/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {}", result1);
+
+    let result2 = solve_part2(&input_text);
+    println!("Part 2: {}", result2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), 18);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), 54);
+    }
+}
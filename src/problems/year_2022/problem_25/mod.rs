@@ -0,0 +1,40 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::utils::snafu::Snafu;
+
+fn parse_input(input: &str) -> Vec<Snafu> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse().unwrap_or_else(|e| panic!("{}", e)))
+        .collect()
+}
+
+fn solve_part1(input_text: &str) -> Snafu {
+    parse_input(input_text).into_iter().sum()
+}
+
+pub fn solve() {
+    println!("Problem 25");
+    let input_path = PathBuf::from("src/problems/year_2022/problem_25/input.txt");
+    let input_text = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    let result1 = solve_part1(&input_text);
+    println!("Part 1: {} (decimal {})", result1, result1.value());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "1=-0-2\n12111\n2=0=\n21\n2=01\n111\n20012\n112\n1=-1=\n1-12\n12\n1=\n122\n";
+
+    #[test]
+    fn part1_example() {
+        let total = solve_part1(EXAMPLE);
+        assert_eq!(total.value(), 4890);
+        assert_eq!(total.to_string(), "2=-1=0");
+    }
+}
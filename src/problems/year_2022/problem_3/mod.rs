@@ -1,59 +1,425 @@
+use std::fmt;
 use std::fs::File;
 use std::io::BufRead;
-use std::collections::HashSet;
 
-fn get_item_priority(item: char) -> u32 {
+/// Rucksacks are searched for badges in groups of this many elves
+const GROUP_SIZE: usize = 3;
+
+/// A set of item types, with bit `priority - 1` set for each type present
+type ItemMask = u64;
+
+/// Why a line or group has no single item in common
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Problem {
+    InvalidItem(char),
+    OddLength(usize),
+    NoCommonItem,
+    MultipleCommonItems(Vec<char>),
+    IncompleteGroup(usize),
+}
+
+/// A problem found on a line, or in the group starting at that line
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Diagnostic {
+    line: usize,
+    problem: Problem,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.problem {
+            Problem::InvalidItem(c) => write!(f, "invalid item type '{}'", c),
+            Problem::OddLength(len) => write!(f, "{} items can't be split into two equal compartments", len),
+            Problem::NoCommonItem => write!(f, "no item in common"),
+            Problem::MultipleCommonItems(items) => {
+                let items: String = items.iter().collect();
+                write!(f, "several items in common ({})", items)
+            }
+            Problem::IncompleteGroup(size) => write!(f, "group has only {} rucksacks", size),
+        }
+    }
+}
+
+fn get_item_priority(item: char) -> Option<u32> {
     if item.is_ascii_lowercase() {
-        (item as u32) - ('a' as u32) + 1
+        Some((item as u32) - ('a' as u32) + 1)
     } else if item.is_ascii_uppercase() {
-        (item as u32) - ('A' as u32) + 27
+        Some((item as u32) - ('A' as u32) + 27)
     } else {
-        panic!("Invalid item type");
+        None
     }
 }
 
-fn find_common_item(rucksack: &str) -> char {
-    let mid = rucksack.len() / 2;
-    let compartment1: HashSet<char> = rucksack[..mid].chars().collect();
-    let compartment2: HashSet<char> = rucksack[mid..].chars().collect();
-    
-    *compartment1.intersection(&compartment2)
-        .next()
-        .expect("No common item found")
+fn item_for_priority(priority: u32) -> char {
+    if priority <= 26 {
+        (b'a' + (priority - 1) as u8) as char
+    } else {
+        (b'A' + (priority - 27) as u8) as char
+    }
+}
+
+fn item_mask(items: &str) -> Result<ItemMask, Problem> {
+    items.chars().try_fold(0, |mask, item| {
+        let priority = get_item_priority(item).ok_or(Problem::InvalidItem(item))?;
+        Ok(mask | 1 << (priority - 1))
+    })
+}
+
+fn items_in(mask: ItemMask) -> Vec<char> {
+    (1..=52).filter(|p| mask & 1 << (p - 1) != 0).map(item_for_priority).collect()
+}
+
+/// The priority of the only item in the set
+fn single_priority(mask: ItemMask) -> Result<u32, Problem> {
+    match mask.count_ones() {
+        0 => Err(Problem::NoCommonItem),
+        1 => Ok(mask.trailing_zeros() + 1),
+        _ => Err(Problem::MultipleCommonItems(items_in(mask))),
+    }
+}
+
+/// Splits a rucksack into its two compartments. Items are checked first, so
+/// the split always falls between two (single-byte) items.
+fn compartments(rucksack: &str) -> Result<(&str, &str), Problem> {
+    if let Some(item) = rucksack.chars().find(|&c| get_item_priority(c).is_none()) {
+        return Err(Problem::InvalidItem(item));
+    }
+    if !rucksack.len().is_multiple_of(2) {
+        return Err(Problem::OddLength(rucksack.len()));
+    }
+    Ok(rucksack.split_at(rucksack.len() / 2))
+}
+
+fn find_common_item(rucksack: &str) -> Result<u32, Problem> {
+    let (first, second) = compartments(rucksack)?;
+    single_priority(item_mask(first)? & item_mask(second)?)
+}
+
+fn find_badge_item(rucksacks: &[String], group_size: usize) -> Result<u32, Problem> {
+    if rucksacks.len() != group_size {
+        return Err(Problem::IncompleteGroup(rucksacks.len()));
+    }
+    let common = rucksacks
+        .iter()
+        .try_fold(ItemMask::MAX, |common, rucksack| Ok(common & item_mask(rucksack)?))?;
+    single_priority(common)
+}
+
+/// Sums the priorities of the items found in both compartments, reporting
+/// the lines where there isn't exactly one
+fn compartment_priorities(lines: &[String]) -> (u32, Vec<Diagnostic>) {
+    let mut total = 0;
+    let mut diagnostics = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match find_common_item(line) {
+            Ok(priority) => total += priority,
+            Err(problem) => diagnostics.push(Diagnostic { line: i + 1, problem }),
+        }
+    }
+    (total, diagnostics)
+}
+
+/// Sums the priorities of the badges of each group of `group_size` elves,
+/// reporting the groups without exactly one
+fn badge_priorities(lines: &[String], group_size: usize) -> (u32, Vec<Diagnostic>) {
+    let mut total = 0;
+    let mut diagnostics = Vec::new();
+    for (i, group) in lines.chunks(group_size).enumerate() {
+        match find_badge_item(group, group_size) {
+            Ok(priority) => total += priority,
+            Err(problem) => diagnostics.push(Diagnostic {
+                line: i * group_size + 1,
+                problem,
+            }),
+        }
+    }
+    (total, diagnostics)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compartment {
+    First,
+    Second,
+}
+
+/// Moving `count` items of one type out of a compartment into the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ItemMove {
+    item: char,
+    count: usize,
+    from: Compartment,
 }
 
-fn find_badge_item(rucksacks: &[String]) -> char {
-    let mut common_items: HashSet<char> = rucksacks[0].chars().collect();
-    
-    for rucksack in &rucksacks[1..] {
-        let rucksack_items: HashSet<char> = rucksack.chars().collect();
-        common_items = common_items.intersection(&rucksack_items).copied().collect();
+impl fmt::Display for ItemMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (from, to) = match self.from {
+            Compartment::First => ("first", "second"),
+            Compartment::Second => ("second", "first"),
+        };
+        write!(f, "move {} x '{}' from the {} compartment to the {}", self.count, self.item, from, to)
     }
-    
-    *common_items.iter().next().expect("No common badge found")
+}
+
+/// Number of items of each type, indexed by `priority - 1`
+fn item_counts(items: &str) -> Result<[usize; 52], Problem> {
+    let mut counts = [0; 52];
+    for item in items.chars() {
+        let priority = get_item_priority(item).ok_or(Problem::InvalidItem(item))?;
+        counts[priority as usize - 1] += 1;
+    }
+    Ok(counts)
+}
+
+/// The fewest item moves that leave no item type in both compartments: every
+/// shared type moves out of whichever compartment holds fewer of it (the
+/// second one on a tie). Compartments may end up different sizes.
+fn plan_reorganisation(rucksack: &str) -> Result<Vec<ItemMove>, Problem> {
+    let (first_items, second_items) = compartments(rucksack)?;
+    let first = item_counts(first_items)?;
+    let second = item_counts(second_items)?;
+    let shared = item_mask(first_items)? & item_mask(second_items)?;
+
+    Ok(items_in(shared)
+        .into_iter()
+        .map(|item| {
+            let index = get_item_priority(item).unwrap() as usize - 1;
+            let (count, from) = if first[index] < second[index] {
+                (first[index], Compartment::First)
+            } else {
+                (second[index], Compartment::Second)
+            };
+            ItemMove { item, count, from }
+        })
+        .collect())
+}
+
+/// How often an item type had to be moved across all rucksacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MisplacedItem {
+    item: char,
+    rucksacks: usize,
+    items_moved: usize,
+}
+
+/// Plans every rucksack, returning the plans by line, the item types that
+/// needed moving (most rucksacks first) and the lines that couldn't be planned
+fn plan_all(lines: &[String]) -> (Vec<Vec<ItemMove>>, Vec<MisplacedItem>, Vec<Diagnostic>) {
+    let mut plans = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut tally = [(0, 0); 52];
+    for (i, line) in lines.iter().enumerate() {
+        match plan_reorganisation(line) {
+            Ok(plan) => {
+                for mov in &plan {
+                    let entry = &mut tally[get_item_priority(mov.item).unwrap() as usize - 1];
+                    entry.0 += 1;
+                    entry.1 += mov.count;
+                }
+                plans.push(plan);
+            }
+            Err(problem) => {
+                diagnostics.push(Diagnostic { line: i + 1, problem });
+                plans.push(Vec::new());
+            }
+        }
+    }
+
+    let mut misplaced: Vec<MisplacedItem> = tally
+        .iter()
+        .enumerate()
+        .filter(|(_, &(rucksacks, _))| rucksacks > 0)
+        .map(|(i, &(rucksacks, items_moved))| MisplacedItem {
+            item: item_for_priority(i as u32 + 1),
+            rucksacks,
+            items_moved,
+        })
+        .collect();
+    misplaced.sort_by_key(|m| (std::cmp::Reverse(m.rucksacks), std::cmp::Reverse(m.items_moved), m.item));
+    (plans, misplaced, diagnostics)
 }
 
 pub fn solve() {
     println!("Problem 3");
     let file = File::open("src/problems/year_2022/problem_3/input.txt").unwrap();
     let reader = std::io::BufReader::new(file);
-    let lines: Vec<String> = reader.lines().map(|l| l.unwrap()).collect();
+    let lines: Vec<String> = reader
+        .lines()
+        .map(|l| l.unwrap().trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
 
     // Part 1
-    let mut total_priority = 0;
-    for line in &lines {
-        let common_item = find_common_item(line);
-        let priority = get_item_priority(common_item);
-        total_priority += priority;
+    let (total_priority, diagnostics) = compartment_priorities(&lines);
+    for diagnostic in &diagnostics {
+        eprintln!("Warning: {}", diagnostic);
     }
     println!("Part 1 - Total priority: {}", total_priority);
 
     // Part 2
-    let mut badge_priority = 0;
-    for group in lines.chunks(3) {
-        let badge = find_badge_item(group);
-        let priority = get_item_priority(badge);
-        badge_priority += priority;
+    let (badge_priority, diagnostics) = badge_priorities(&lines, GROUP_SIZE);
+    for diagnostic in &diagnostics {
+        eprintln!("Warning: {}", diagnostic);
     }
     println!("Part 2 - Total badge priority: {}", badge_priority);
-} 
\ No newline at end of file
+
+    // Reorganisation
+    let (plans, misplaced, diagnostics) = plan_all(&lines);
+    for diagnostic in &diagnostics {
+        eprintln!("Warning: {}", diagnostic);
+    }
+    let total_moves: usize = plans.iter().flatten().map(|mov| mov.count).sum();
+    println!("Reorganisation - Items to move: {} across {} rucksacks", total_moves, plans.len());
+    for (i, plan) in plans.iter().enumerate() {
+        for mov in plan {
+            println!("  Rucksack {}: {}", i + 1, mov);
+        }
+    }
+    let most_misplaced: Vec<String> = misplaced
+        .iter()
+        .take(5)
+        .map(|m| format!("'{}' ({} rucksacks, {} items)", m.item, m.rucksacks, m.items_moved))
+        .collect();
+    println!("Most often misplaced: {}", most_misplaced.join(", "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(compartment_priorities(&lines(EXAMPLE)), (157, Vec::new()));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(badge_priorities(&lines(EXAMPLE), GROUP_SIZE), (70, Vec::new()));
+    }
+
+    #[test]
+    fn finds_badges_in_groups_of_any_size() {
+        assert_eq!(badge_priorities(&lines("aXb\ncXd\neYf\nYgh\n"), 2), (50 + 51, Vec::new()));
+        assert_eq!(badge_priorities(&lines("ak\nbk\nck\ndk\nek\nfk\n"), 6), (11, Vec::new()));
+
+        // Neighbouring example rucksacks share several items, and none is in all six
+        let lines = lines(EXAMPLE);
+        let diagnostic = |line, problem| Diagnostic { line, problem };
+        assert_eq!(
+            badge_priorities(&lines, 2),
+            (
+                0,
+                vec![
+                    diagnostic(1, Problem::MultipleCommonItems(vec!['f', 'r', 's', 'F', 'M'])),
+                    diagnostic(3, Problem::MultipleCommonItems(vec!['q', 'v', 'w', 'B', 'T'])),
+                    diagnostic(5, Problem::MultipleCommonItems(vec!['G', 'J', 'Z'])),
+                ]
+            )
+        );
+        assert_eq!(badge_priorities(&lines, 6), (0, vec![diagnostic(1, Problem::NoCommonItem)]));
+    }
+
+    #[test]
+    fn reports_short_last_group() {
+        let lines = lines(EXAMPLE);
+        assert_eq!(
+            badge_priorities(&lines[..5], 3),
+            (
+                18,
+                vec![Diagnostic {
+                    line: 4,
+                    problem: Problem::IncompleteGroup(2),
+                }]
+            )
+        );
+        assert_eq!(
+            badge_priorities(&lines, 4),
+            (
+                0,
+                vec![
+                    Diagnostic {
+                        line: 1,
+                        problem: Problem::NoCommonItem,
+                    },
+                    Diagnostic {
+                        line: 5,
+                        problem: Problem::IncompleteGroup(2),
+                    },
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn reports_bad_rucksacks() {
+        assert_eq!(find_common_item("a\u{e9}b"), Err(Problem::InvalidItem('\u{e9}')));
+        assert_eq!(find_common_item("a\u{e9}"), Err(Problem::InvalidItem('\u{e9}')));
+        assert_eq!(find_common_item("abc"), Err(Problem::OddLength(3)));
+        assert_eq!(find_common_item("abcd"), Err(Problem::NoCommonItem));
+        assert_eq!(find_common_item("abba"), Err(Problem::MultipleCommonItems(vec!['a', 'b'])));
+
+        let (total, diagnostics) = compartment_priorities(&lines("aa\na\u{e9}b\nBB\n"));
+        assert_eq!(total, 1 + 28);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                line: 2,
+                problem: Problem::InvalidItem('\u{e9}'),
+            }]
+        );
+    }
+
+    #[test]
+    fn plans_reorganisation() {
+        // 'p' is shared between the compartments of the first example rucksack
+        assert_eq!(
+            plan_reorganisation("vJrwpWtwJgWrhcsFMMfFFhFp"),
+            Ok(vec![ItemMove {
+                item: 'p',
+                count: 1,
+                from: Compartment::Second,
+            }])
+        );
+        assert_eq!(
+            plan_reorganisation("aaba"),
+            Ok(vec![ItemMove {
+                item: 'a',
+                count: 1,
+                from: Compartment::Second,
+            }])
+        );
+        assert_eq!(plan_reorganisation("a\u{e9}b"), Err(Problem::InvalidItem('\u{e9}')));
+
+        let (plans, misplaced, diagnostics) = plan_all(&lines("abba\na\u{e9}b\nabcd\n"));
+        assert_eq!(plans.len(), 3);
+        assert_eq!(plans[0].len(), 2);
+        assert!(plans[1].is_empty() && plans[2].is_empty());
+        assert_eq!(
+            misplaced.first(),
+            Some(&MisplacedItem {
+                item: 'a',
+                rucksacks: 1,
+                items_moved: 1,
+            })
+        );
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                line: 2,
+                problem: Problem::InvalidItem('\u{e9}'),
+            }]
+        );
+    }
+}
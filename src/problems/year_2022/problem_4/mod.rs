@@ -1,7 +1,8 @@
+use std::fmt;
 use std::fs::File;
 use std::io::BufRead;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Range {
     start: u32,
     end: u32,
@@ -17,6 +18,145 @@ impl Range {
         // and the other range's start is less than or equal to the first range's end
         self.start <= other.end && other.start <= self.end
     }
+
+    fn len(&self) -> u32 {
+        self.end - self.start + 1
+    }
+
+    fn intersection(&self, other: &Range) -> Option<Range> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(Range {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// Number of sections both ranges cover
+    fn overlap_size(&self, other: &Range) -> u32 {
+        self.intersection(other).map_or(0, |r| r.len())
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// An elf, identified by its 0-based pair and its position (0 or 1) in the pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ElfId {
+    pair: usize,
+    position: usize,
+}
+
+impl fmt::Display for ElfId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pair {} elf {}", self.pair + 1, self.position + 1)
+    }
+}
+
+/// What the assignments of every pair look like taken together
+#[derive(Debug)]
+struct Analysis {
+    /// Sections shared by the two elves of each pair
+    pair_overlaps: Vec<u32>,
+    /// Sections assigned to at least one elf
+    union_size: u32,
+    /// The most elves assigned to any one section
+    max_coverage: u32,
+    /// The runs of sections assigned to `max_coverage` elves
+    most_covered: Vec<Range>,
+    /// Elves whose whole assignment is also assigned to some other single elf.
+    /// Of several elves with identical assignments, all but the first are redundant.
+    redundant_elves: Vec<ElfId>,
+}
+
+fn analyse(pairs: &[(Range, Range)]) -> Analysis {
+    let pair_overlaps = pairs.iter().map(|(a, b)| a.overlap_size(b)).collect();
+
+    let elves: Vec<(ElfId, Range)> = pairs
+        .iter()
+        .enumerate()
+        .flat_map(|(pair, (a, b))| {
+            [
+                (ElfId { pair, position: 0 }, *a),
+                (ElfId { pair, position: 1 }, *b),
+            ]
+        })
+        .collect();
+
+    // Sweep over range starts and ends to track how many elves cover each section
+    let mut events: Vec<(u32, i32)> = elves
+        .iter()
+        .flat_map(|(_, r)| [(r.start, 1), (r.end + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut union_size = 0;
+    let mut max_coverage = 0;
+    let mut most_covered: Vec<Range> = Vec::new();
+    let mut coverage = 0;
+    let mut i = 0;
+    while i < events.len() {
+        let position = events[i].0;
+        while i < events.len() && events[i].0 == position {
+            coverage += events[i].1;
+            i += 1;
+        }
+        // Coverage is constant from here up to the next event
+        let Some(&(next, _)) = events.get(i) else {
+            break;
+        };
+        let run = Range {
+            start: position,
+            end: next - 1,
+        };
+        let count = coverage as u32;
+        if count > 0 {
+            union_size += run.len();
+        }
+        if count > max_coverage {
+            max_coverage = count;
+            most_covered.clear();
+        }
+        if count == max_coverage && count > 0 {
+            match most_covered.last_mut() {
+                Some(last) if last.end + 1 == run.start => last.end = run.end,
+                _ => most_covered.push(run),
+            }
+        }
+    }
+
+    // Sorted by start, widest first, an elf is covered by another exactly when
+    // some earlier elf reaches at least as far
+    let mut order: Vec<usize> = (0..elves.len()).collect();
+    order.sort_by_key(|&i| (elves[i].1.start, std::cmp::Reverse(elves[i].1.end), i));
+    let mut redundant = vec![false; elves.len()];
+    let mut furthest_end: Option<u32> = None;
+    for &i in &order {
+        let end = elves[i].1.end;
+        match furthest_end {
+            Some(furthest) if furthest >= end => redundant[i] = true,
+            _ => furthest_end = Some(end),
+        }
+    }
+    let redundant_elves = elves
+        .iter()
+        .zip(&redundant)
+        .filter(|&(_, &r)| r)
+        .map(|((id, _), _)| *id)
+        .collect();
+
+    Analysis {
+        pair_overlaps,
+        union_size,
+        max_coverage,
+        most_covered,
+        redundant_elves,
+    }
 }
 
 fn parse_range(range_str: &str) -> Range {
@@ -25,8 +165,8 @@ fn parse_range(range_str: &str) -> Range {
         panic!("Invalid range format: '{}'", range_str);
     }
     Range {
-        start: parts[0].parse().expect(&format!("Invalid start number in range: '{}'", range_str)),
-        end: parts[1].parse().expect(&format!("Invalid end number in range: '{}'", range_str)),
+        start: parts[0].parse().unwrap_or_else(|_| panic!("Invalid start number in range: '{}'", range_str)),
+        end: parts[1].parse().unwrap_or_else(|_| panic!("Invalid end number in range: '{}'", range_str)),
     }
 }
 
@@ -46,6 +186,7 @@ pub fn solve() {
     let mut fully_contained_count = 0;
     let mut overlapping_count = 0;
     let mut line_number = 0;
+    let mut pairs = Vec::new();
 
     for line in reader.lines() {
         line_number += 1;
@@ -70,8 +211,129 @@ pub fn solve() {
         if range1.overlaps(&range2) {
             overlapping_count += 1;
         }
+
+        pairs.push((range1, range2));
     }
 
     println!("Part 1 - Number of pairs where one range fully contains the other: {}", fully_contained_count);
     println!("Part 2 - Number of pairs where ranges overlap: {}", overlapping_count);
-} 
\ No newline at end of file
+
+    let analysis = analyse(&pairs);
+    if let Some((pair, overlap)) = analysis
+        .pair_overlaps
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, &overlap)| (overlap, std::cmp::Reverse(i)))
+    {
+        println!("Largest overlap: {} sections, in pair {}", overlap, pair + 1);
+    }
+    println!("Sections assigned to at least one elf: {}", analysis.union_size);
+    let most_covered: Vec<String> = analysis.most_covered.iter().map(Range::to_string).collect();
+    println!(
+        "Most heavily covered sections ({} elves each): {}",
+        analysis.max_coverage,
+        most_covered.join(", ")
+    );
+    println!(
+        "Elves whose whole assignment another elf also covers: {}",
+        analysis.redundant_elves.len()
+    );
+    if let Some(elf) = analysis.redundant_elves.first() {
+        println!("First redundant elf: {}", elf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 6] = ["2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8"];
+
+    fn range(start: u32, end: u32) -> Range {
+        Range { start, end }
+    }
+
+    fn elf(pair: usize, position: usize) -> ElfId {
+        ElfId { pair, position }
+    }
+
+    #[test]
+    fn counts_example_pairs() {
+        let pairs: Vec<(Range, Range)> = EXAMPLE.iter().map(|line| parse_line(line)).collect();
+        let contained = pairs.iter().filter(|(a, b)| a.contains(b) || b.contains(a)).count();
+        let overlapping = pairs.iter().filter(|(a, b)| a.overlaps(b)).count();
+        assert_eq!((contained, overlapping), (2, 4));
+    }
+
+    #[test]
+    fn analyses_example() {
+        let pairs: Vec<(Range, Range)> = EXAMPLE.iter().map(|line| parse_line(line)).collect();
+        let analysis = analyse(&pairs);
+        assert_eq!(analysis.pair_overlaps, vec![0, 0, 1, 5, 1, 3]);
+        assert_eq!(analysis.union_size, 8);
+        assert_eq!(analysis.max_coverage, 8);
+        assert_eq!(analysis.most_covered, vec![range(6, 6)]);
+        // Only 7-9 and 2-8 aren't inside some other elf's assignment
+        assert_eq!(
+            analysis.redundant_elves,
+            vec![
+                elf(0, 0),
+                elf(0, 1),
+                elf(1, 0),
+                elf(1, 1),
+                elf(2, 0),
+                elf(3, 1),
+                elf(4, 0),
+                elf(4, 1),
+                elf(5, 0),
+                elf(5, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn measures_pair_overlaps() {
+        assert_eq!(range(1, 5).overlap_size(&range(3, 7)), 3);
+        assert_eq!(range(3, 7).overlap_size(&range(1, 5)), 3);
+        assert_eq!(range(1, 2).overlap_size(&range(3, 4)), 0);
+        assert_eq!(range(4, 4).overlap_size(&range(1, 9)), 1);
+        assert_eq!(range(2, 4).overlap_size(&range(2, 4)), 3);
+    }
+
+    #[test]
+    fn keeps_disjoint_runs_apart() {
+        let analysis = analyse(&[(range(1, 2), range(5, 6))]);
+        assert_eq!(analysis.pair_overlaps, vec![0]);
+        assert_eq!(analysis.union_size, 4);
+        assert_eq!(analysis.max_coverage, 1);
+        assert_eq!(analysis.most_covered, vec![range(1, 2), range(5, 6)]);
+        assert!(analysis.redundant_elves.is_empty());
+    }
+
+    #[test]
+    fn merges_touching_runs() {
+        let analysis = analyse(&[(range(1, 2), range(3, 4)), (range(5, 5), range(8, 9))]);
+        assert_eq!(analysis.union_size, 7);
+        assert_eq!(analysis.max_coverage, 1);
+        assert_eq!(analysis.most_covered, vec![range(1, 5), range(8, 9)]);
+    }
+
+    #[test]
+    fn finds_max_coverage() {
+        let analysis = analyse(&[(range(1, 5), range(3, 7)), (range(4, 4), range(9, 9))]);
+        assert_eq!(analysis.pair_overlaps, vec![3, 0]);
+        assert_eq!(analysis.union_size, 8);
+        assert_eq!(analysis.max_coverage, 3);
+        assert_eq!(analysis.most_covered, vec![range(4, 4)]);
+        assert_eq!(analysis.redundant_elves, vec![elf(1, 0)]);
+    }
+
+    #[test]
+    fn keeps_first_of_identical_assignments() {
+        let analysis = analyse(&[(range(2, 4), range(2, 4)), (range(2, 4), range(1, 1))]);
+        assert_eq!(analysis.pair_overlaps, vec![3, 0]);
+        assert_eq!(analysis.max_coverage, 3);
+        assert_eq!(analysis.most_covered, vec![range(2, 4)]);
+        assert_eq!(analysis.redundant_elves, vec![elf(0, 1), elf(1, 0)]);
+    }
+}
@@ -1,69 +1,430 @@
-use std::fs::File;
+use std::env;
+use std::fs::{self, File};
 use std::io::BufRead;
-use std::collections::VecDeque;
+use std::fmt;
 
-#[derive(Debug)]
+use crate::utils::tui::{self, Simulation};
+
+#[derive(Debug, Clone)]
 struct Move {
     quantity: usize,
     from: usize,
     to: usize,
 }
 
-fn parse_stacks(lines: &[String]) -> Vec<VecDeque<char>> {
-    // Find the number of stacks from the last line (contains stack numbers)
-    let stack_count = lines.last()
-        .unwrap()
-        .split_whitespace()
-        .last()
-        .unwrap()
-        .parse::<usize>()
-        .unwrap();
-    
-    let mut stacks = vec![VecDeque::new(); stack_count];
-    
-    // Process each line except the last one (which contains stack numbers)
-    for line in &lines[..lines.len()-1] {
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.quantity, self.from + 1, self.to + 1)
+    }
+}
+
+/// Crate stacks, listed bottom first, so the top crate of each is the last one
+type Stacks = Vec<Vec<char>>;
+
+/// Parses a drawing of crate stacks. Lines may be ragged or missing trailing
+/// spaces, and the stack number line may be left out, in which case stacks are
+/// taken to be four columns apart. Each crate belongs to the stack whose
+/// number is drawn closest to it, so numbers of any width are fine. Crate
+/// labels must be a single character; `[AB]` is rejected.
+fn parse_stacks<S: AsRef<str>>(lines: &[S]) -> Result<Stacks, String> {
+    let mut lines: Vec<&str> = lines.iter().map(|l| l.as_ref().trim_end()).collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+
+    // Column span of each stack number, in order
+    let is_number_line = |line: &str| {
+        !line.trim().is_empty() && line.split_whitespace().all(|t| t.chars().all(|c| c.is_ascii_digit()))
+    };
+    let labels: Option<Vec<(usize, usize)>> = match lines.last() {
+        Some(&line) if is_number_line(line) => {
+            lines.pop();
+            let mut labels = Vec::new();
+            let mut column = 0;
+            for token in line.split_whitespace() {
+                let start = column + line[column..].find(token).unwrap();
+                column = start + token.len();
+                let number: usize = token.parse().map_err(|e| format!("Invalid stack number '{}': {}", token, e))?;
+                if number != labels.len() + 1 {
+                    return Err(format!("Expected stack {} but found stack {}", labels.len() + 1, number));
+                }
+                labels.push((start, column - 1));
+            }
+            Some(labels)
+        }
+        _ => None,
+    };
+
+    // Centre column of every crate, row by row from the top
+    let mut rows: Vec<Vec<(usize, char)>> = Vec::new();
+    for (row, line) in lines.iter().enumerate() {
         let chars: Vec<char> = line.chars().collect();
-        for (i, chunk) in chars.chunks(4).enumerate() {
-            if chunk[1] != ' ' {
-                stacks[i].push_back(chunk[1]);
+        let mut crates = Vec::new();
+        let mut column = 0;
+        while column < chars.len() {
+            match chars[column] {
+                ' ' => column += 1,
+                '[' if chars.get(column + 2) == Some(&']') && chars[column + 1] != ' ' => {
+                    crates.push((column + 1, chars[column + 1]));
+                    column += 3;
+                }
+                '[' => {
+                    // Only a wider label can be closed by a bracket further along
+                    let label: String = chars[column + 1..]
+                        .iter()
+                        .take_while(|&&c| !matches!(c, ']' | '[' | ' '))
+                        .collect();
+                    if !label.is_empty() && chars.get(column + 1 + label.chars().count()) == Some(&']') {
+                        return Err(format!(
+                            "Crate labels must be a single character, not '[{}]' at row {}, column {} of the drawing",
+                            label,
+                            row + 1,
+                            column + 1
+                        ));
+                    }
+                    return Err(format!("Unexpected '[' at row {}, column {} of the drawing", row + 1, column + 1));
+                }
+                c => {
+                    return Err(format!("Unexpected '{}' at row {}, column {} of the drawing", c, row + 1, column + 1));
+                }
             }
         }
+        rows.push(crates);
     }
-    
-    stacks
+
+    let stack_for = |column: usize| -> usize {
+        match &labels {
+            Some(labels) => {
+                let distance = |&(start, end): &(usize, usize)| {
+                    if column < start {
+                        start - column
+                    } else {
+                        column.saturating_sub(end)
+                    }
+                };
+                (0..labels.len()).min_by_key(|&i| distance(&labels[i])).unwrap_or(0)
+            }
+            None => column / 4,
+        }
+    };
+    let stack_count = match &labels {
+        Some(labels) => labels.len(),
+        None => rows.iter().flatten().map(|&(column, _)| stack_for(column) + 1).max().unwrap_or(0),
+    };
+    if stack_count == 0 && rows.iter().any(|row| !row.is_empty()) {
+        return Err("Crates are drawn but there are no stacks".to_string());
+    }
+
+    let mut stacks = vec![Vec::new(); stack_count];
+    for (height, (row, crates)) in rows.iter().enumerate().rev().enumerate() {
+        for &(column, crate_) in crates {
+            let stack = stack_for(column);
+            if stacks[stack].len() != height {
+                return Err(format!(
+                    "Crate [{}] at row {} of the drawing isn't resting on stack {}",
+                    crate_,
+                    row + 1,
+                    stack + 1
+                ));
+            }
+            stacks[stack].push(crate_);
+        }
+    }
+
+    Ok(stacks)
 }
 
-fn parse_move(line: &str) -> Move {
+fn parse_move(line: &str) -> Result<Move, String> {
     let parts: Vec<&str> = line.split_whitespace().collect();
-    Move {
-        quantity: parts[1].parse().unwrap(),
-        from: parts[3].parse::<usize>().unwrap() - 1, // Convert to 0-based index
-        to: parts[5].parse::<usize>().unwrap() - 1,   // Convert to 0-based index
+    let [_, quantity, _, from, _, to] = parts[..] else {
+        return Err(format!("Invalid move '{}'", line));
+    };
+    let number = |text: &str| {
+        text.parse::<usize>()
+            .map_err(|e| format!("Invalid number '{}' in move '{}': {}", text, line, e))
+    };
+    // Stacks are numbered from 1; convert to a 0-based index
+    let stack = |text: &str| match number(text)? {
+        0 => Err(format!("There is no stack 0 in move '{}'", line)),
+        n => Ok(n - 1),
+    };
+    Ok(Move {
+        quantity: number(quantity)?,
+        from: stack(from)?,
+        to: stack(to)?,
+    })
+}
+
+/// Why a move can't be carried out
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MoveError {
+    NoSuchStack(usize),
+    EmptyStack(usize),
+    NotEnoughCrates {
+        stack: usize,
+        wanted: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::NoSuchStack(stack) => write!(f, "there is no stack {}", stack + 1),
+            MoveError::EmptyStack(stack) => write!(f, "stack {} is empty", stack + 1),
+            MoveError::NotEnoughCrates {
+                stack,
+                wanted,
+                available,
+            } => write!(
+                f,
+                "stack {} has {} crates, not {}",
+                stack + 1,
+                available,
+                wanted
+            ),
+        }
     }
 }
 
-fn apply_move_part1(stacks: &mut Vec<VecDeque<char>>, mov: &Move) {
-    for _ in 0..mov.quantity {
-        if let Some(crate_) = stacks[mov.from].pop_front() {
-            stacks[mov.to].push_front(crate_);
+/// Checks that `count` crates can be lifted off `source` and set down on `target`
+fn check_move(stacks: &Stacks, source: usize, target: usize, count: usize) -> Result<(), MoveError> {
+    for stack in [source, target] {
+        if stack >= stacks.len() {
+            return Err(MoveError::NoSuchStack(stack));
         }
     }
+    let available = stacks[source].len();
+    if count > 0 && available == 0 {
+        return Err(MoveError::EmptyStack(source));
+    }
+    if count > available {
+        return Err(MoveError::NotEnoughCrates {
+            stack: source,
+            wanted: count,
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// A crane model. Every crane lifts the top crates of one stack and sets them
+/// down on another; models differ only in the order the crates land in.
+trait Crane {
+    fn name(&self) -> String;
+
+    /// The order in which `count` lifted crates land, bottom first, as
+    /// indices into the lifted crates (also bottom first)
+    fn arrangement(&self, count: usize) -> Vec<usize>;
+
+    /// Carries out a move, leaving the stacks untouched if it's impossible
+    fn apply(&self, stacks: &mut Stacks, mov: &Move) -> Result<(), MoveError> {
+        check_move(stacks, mov.from, mov.to, mov.quantity)?;
+        // Putting crates back where they came from changes nothing for any crane
+        if mov.from == mov.to {
+            return Ok(());
+        }
+
+        let available = stacks[mov.from].len();
+        let lifted = stacks[mov.from].split_off(available - mov.quantity);
+        let landed = self.arrangement(mov.quantity).into_iter().map(|i| lifted[i]);
+        stacks[mov.to].extend(landed);
+        Ok(())
+    }
+
+    /// Takes back a move this crane made, so that `apply` followed by `undo`
+    /// leaves the stacks as they were
+    fn undo(&self, stacks: &mut Stacks, mov: &Move) -> Result<(), MoveError> {
+        check_move(stacks, mov.to, mov.from, mov.quantity)?;
+        if mov.from == mov.to {
+            return Ok(());
+        }
+
+        let available = stacks[mov.to].len();
+        let landed = stacks[mov.to].split_off(available - mov.quantity);
+        let mut lifted = vec![' '; mov.quantity];
+        for (crate_, i) in landed.into_iter().zip(self.arrangement(mov.quantity)) {
+            lifted[i] = crate_;
+        }
+        stacks[mov.from].extend(lifted);
+        Ok(())
+    }
+}
+
+/// Moves one crate at a time, so the moved crates end up reversed
+#[derive(Debug, Clone, Copy)]
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn arrangement(&self, count: usize) -> Vec<usize> {
+        (0..count).rev().collect()
+    }
+}
+
+/// Moves all the crates at once, keeping their order
+#[derive(Debug, Clone, Copy)]
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn arrangement(&self, count: usize) -> Vec<usize> {
+        (0..count).collect()
+    }
+}
+
+/// Moves up to `capacity` crates at a time, keeping their order within each load
+// Neither puzzle part uses it yet
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+struct CapacityLimitedCrane {
+    capacity: usize,
+}
+
+impl Crane for CapacityLimitedCrane {
+    fn name(&self) -> String {
+        format!("Crane carrying {}", self.capacity)
+    }
+
+    fn arrangement(&self, count: usize) -> Vec<usize> {
+        // The top load lands first, at the bottom of the new pile
+        let order: Vec<usize> = (0..count).collect();
+        order.rchunks(self.capacity.max(1)).flatten().copied().collect()
+    }
+}
+
+/// Runs every move with the given crane, stopping at the first impossible one
+fn run_crane(crane: &dyn Crane, stacks: &mut Stacks, moves: &[Move]) -> Result<(), String> {
+    for (i, mov) in moves.iter().enumerate() {
+        crane
+            .apply(stacks, mov)
+            .map_err(|e| format!("{}: move {} ({}): {}", crane.name(), i + 1, mov, e))?;
+    }
+    Ok(())
+}
+
+/// Works out the starting stacks from the stacks left after all the moves, by
+/// taking the moves back last first. Fails if some move couldn't have happened.
+fn reconstruct_initial(crane: &dyn Crane, final_stacks: &Stacks, moves: &[Move]) -> Result<Stacks, String> {
+    let mut stacks = final_stacks.clone();
+    for (i, mov) in moves.iter().enumerate().rev() {
+        crane
+            .undo(&mut stacks, mov)
+            .map_err(|e| format!("{}: undoing move {} ({}): {}", crane.name(), i + 1, mov, e))?;
+    }
+    Ok(stacks)
+}
+
+/// Draws the stacks in the same format as the puzzle input
+fn render_stacks(stacks: &[Vec<char>]) -> Vec<String> {
+    let tallest = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..tallest)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crate_) => format!("[{}]", crate_),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    // Numbers start under the crate letters; wide ones spill to the right
+    let numbers: Vec<String> = (1..=stacks.len()).map(|i| format!(" {:<2}", i)).collect();
+    lines.push(numbers.join(" "));
+    lines
 }
 
-fn apply_move_part2(stacks: &mut Vec<VecDeque<char>>, mov: &Move) {
-    let mut temp_stack = VecDeque::new();
-    
-    // Move crates to temporary stack
-    for _ in 0..mov.quantity {
-        if let Some(crate_) = stacks[mov.from].pop_front() {
-            temp_stack.push_front(crate_);
+fn top_crates(stacks: &[Vec<char>]) -> String {
+    stacks
+        .iter()
+        .map(|stack| stack.last().copied().unwrap_or(' '))
+        .collect()
+}
+
+/// Returns `Some` if `--show-stacks` or `--show-stacks=<moves>` was passed,
+/// holding the number of moves after which to draw the stacks (all of them if unset)
+fn show_stacks_requested() -> Option<Option<usize>> {
+    env::args().find_map(|arg| {
+        if arg == "--show-stacks" {
+            Some(None)
+        } else {
+            arg.strip_prefix("--show-stacks=").map(|n| n.parse().ok())
         }
+    })
+}
+
+/// Returns the path passed with `--final-stacks=<path>`, a drawing of the
+/// stacks after every move from which to work out the starting stacks
+fn final_stacks_requested() -> Option<String> {
+    env::args().find_map(|arg| arg.strip_prefix("--final-stacks=").map(String::from))
+}
+
+/// Replays the moves one at a time in the terminal UI
+#[derive(Clone)]
+struct CraneSimulation {
+    stacks: Stacks,
+    moves: Vec<Move>,
+    next_move: usize,
+    crane: &'static dyn Crane,
+    error: Option<String>,
+}
+
+impl Simulation for CraneSimulation {
+    fn title(&self) -> String {
+        self.crane.name()
+    }
+
+    fn step(&mut self) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        let Some(mov) = self.moves.get(self.next_move) else {
+            return false;
+        };
+        match self.crane.apply(&mut self.stacks, mov) {
+            Ok(()) => {
+                self.next_move += 1;
+                true
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                false
+            }
+        }
+    }
+
+    fn render(&self, _width: usize, height: usize) -> Vec<String> {
+        // Keep the bottom of the stacks in view
+        let lines = render_stacks(&self.stacks);
+        let skip = lines.len().saturating_sub(height);
+        lines.into_iter().skip(skip).collect()
     }
-    
-    // Move crates from temporary stack to destination
-    while let Some(crate_) = temp_stack.pop_front() {
-        stacks[mov.to].push_front(crate_);
+
+    fn inspect(&self) -> Vec<(String, String)> {
+        let describe = |index: Option<usize>| {
+            index
+                .and_then(|i| self.moves.get(i))
+                .map_or("-".to_string(), Move::to_string)
+        };
+        let mut details = vec![
+            ("Moves".to_string(), format!("{} / {}", self.next_move, self.moves.len())),
+            ("Last".to_string(), describe(self.next_move.checked_sub(1))),
+            ("Next".to_string(), describe(Some(self.next_move))),
+            ("Tops".to_string(), top_crates(&self.stacks)),
+        ];
+        if let Some(error) = &self.error {
+            details.push(("Error".to_string(), error.clone()));
+        }
+        details
     }
 }
 
@@ -78,31 +439,250 @@ pub fn solve() {
         .position(|line| line.trim().is_empty())
         .unwrap();
 
-    // Parse stacks for part 1
-    let mut stacks_part1 = parse_stacks(&lines[..separator_index]);
-    // Parse stacks for part 2
-    let mut stacks_part2 = parse_stacks(&lines[..separator_index]);
-    
-    // Parse and apply moves
-    for line in &lines[separator_index + 1..] {
-        if line.trim().is_empty() {
-            continue;
+    let initial = parse_stacks(&lines[..separator_index]).expect("Invalid stack drawing");
+
+    let moves: Vec<Move> = lines[separator_index + 1..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_move(line).expect("Invalid move"))
+        .collect();
+
+    let parts: [(&str, &dyn Crane); 2] = [("Part 1", &CrateMover9000), ("Part 2", &CrateMover9001)];
+    for (part, crane) in parts {
+        let mut stacks = initial.clone();
+        match run_crane(crane, &mut stacks, &moves) {
+            Ok(()) => println!("{} - Top crates: {}", part, top_crates(&stacks)),
+            Err(e) => eprintln!("{} failed: {}", part, e),
         }
-        let mov = parse_move(line);
-        apply_move_part1(&mut stacks_part1, &mov);
-        apply_move_part2(&mut stacks_part2, &mov);
     }
 
-    // Get top crates for part 1
-    let top_crates_part1: String = stacks_part1.iter()
-        .map(|stack| stack.front().unwrap())
-        .collect();
+    if let Some(after) = show_stacks_requested() {
+        let after = after.unwrap_or(moves.len()).min(moves.len());
+        for (part, crane) in parts {
+            let mut stacks = initial.clone();
+            match run_crane(crane, &mut stacks, &moves[..after]) {
+                Ok(()) => {
+                    println!("{} - Stacks after {} moves:", part, after);
+                    for line in render_stacks(&stacks) {
+                        println!("{}", line);
+                    }
+                }
+                Err(e) => eprintln!("{} failed: {}", part, e),
+            }
+        }
+    }
 
-    // Get top crates for part 2
-    let top_crates_part2: String = stacks_part2.iter()
-        .map(|stack| stack.front().unwrap())
-        .collect();
+    if let Some(path) = final_stacks_requested() {
+        match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|drawing| parse_stacks(&drawing.lines().collect::<Vec<_>>()))
+        {
+            Ok(final_stacks) => {
+                for (part, crane) in parts {
+                    match reconstruct_initial(crane, &final_stacks, &moves) {
+                        Ok(stacks) => {
+                            println!("{} - Starting stacks for {}:", part, path);
+                            for line in render_stacks(&stacks) {
+                                println!("{}", line);
+                            }
+                        }
+                        Err(e) => eprintln!("{} failed: {}", part, e),
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to read final stacks from {}: {}", path, e),
+        }
+    }
 
-    println!("Part 1 - Top crates: {}", top_crates_part1);
-    println!("Part 2 - Top crates: {}", top_crates_part2);
-} 
\ No newline at end of file
+    if tui::requested() {
+        let simulation = |crane: &'static dyn Crane| CraneSimulation {
+            stacks: initial.clone(),
+            moves: moves.clone(),
+            next_move: 0,
+            crane,
+            error: None,
+        };
+        let simulations = vec![simulation(&CrateMover9000), simulation(&CrateMover9001)];
+        if let Err(e) = tui::run(simulations) {
+            eprintln!("Terminal UI failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 4] = ["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "];
+
+    #[test]
+    fn parses_example() {
+        let stacks = parse_stacks(&EXAMPLE).unwrap();
+        assert_eq!(stacks, vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+    }
+
+    #[test]
+    fn drawing_round_trips() {
+        let stacks = parse_stacks(&EXAMPLE).unwrap();
+        assert_eq!(render_stacks(&stacks), EXAMPLE);
+    }
+
+    #[test]
+    fn round_trips_after_moves() {
+        let mut stacks = parse_stacks(&EXAMPLE).unwrap();
+        let moves = [
+            Move { quantity: 1, from: 1, to: 0 },
+            Move { quantity: 3, from: 0, to: 2 },
+        ];
+        run_crane(&CrateMover9000, &mut stacks, &moves).unwrap();
+        let drawing = render_stacks(&stacks);
+        assert_eq!(drawing, ["        [Z]", "        [N]", "    [C] [D]", "    [M] [P]", " 1   2   3 "]);
+        assert_eq!(parse_stacks(&drawing).unwrap(), stacks);
+    }
+
+    #[test]
+    fn parses_moves() {
+        let mov = parse_move("move 3 from 1 to 12").unwrap();
+        assert_eq!((mov.quantity, mov.from, mov.to), (3, 0, 11));
+        assert_eq!(mov.to_string(), "move 3 from 1 to 12");
+        assert_eq!(
+            parse_move("move 1 from 0 to 2").unwrap_err(),
+            "There is no stack 0 in move 'move 1 from 0 to 2'"
+        );
+        assert!(parse_move("move 1 from 2 to 0").is_err());
+        assert!(parse_move("move 1 from 2").is_err());
+        assert!(parse_move("move x from 1 to 2").is_err());
+        assert!(parse_move("move -1 from 1 to 2").is_err());
+    }
+
+    #[test]
+    fn impossible_moves_leave_the_stacks_alone() {
+        let initial = parse_stacks(&EXAMPLE).unwrap();
+        let mut emptied = initial.clone();
+        emptied[2].clear();
+        let cases = [
+            (&initial, Move { quantity: 1, from: 3, to: 0 }, MoveError::NoSuchStack(3)),
+            (&initial, Move { quantity: 1, from: 0, to: 7 }, MoveError::NoSuchStack(7)),
+            (&emptied, Move { quantity: 1, from: 2, to: 0 }, MoveError::EmptyStack(2)),
+            (
+                &initial,
+                Move { quantity: 3, from: 0, to: 1 },
+                MoveError::NotEnoughCrates {
+                    stack: 0,
+                    wanted: 3,
+                    available: 2,
+                },
+            ),
+        ];
+        let cranes: [&dyn Crane; 3] = [&CrateMover9000, &CrateMover9001, &CapacityLimitedCrane { capacity: 2 }];
+        for crane in cranes {
+            for (stacks, mov, error) in &cases {
+                let mut after = (*stacks).clone();
+                assert_eq!(crane.apply(&mut after, mov), Err(error.clone()), "{} {}", crane.name(), mov);
+                assert_eq!(&after, *stacks);
+            }
+        }
+    }
+
+    #[test]
+    fn run_crane_stops_at_the_first_impossible_move() {
+        let mut stacks = parse_stacks(&EXAMPLE).unwrap();
+        let moves = [
+            Move { quantity: 1, from: 2, to: 0 },
+            Move { quantity: 1, from: 2, to: 0 },
+            Move { quantity: 1, from: 0, to: 2 },
+        ];
+        let error = run_crane(&CrateMover9000, &mut stacks, &moves).unwrap_err();
+        assert_eq!(error, "CrateMover 9000: move 2 (move 1 from 3 to 1): stack 3 is empty");
+        // The first move happened, the third never ran
+        assert_eq!(stacks, vec![vec!['Z', 'N', 'P'], vec!['M', 'C', 'D'], vec![]]);
+    }
+
+    #[test]
+    fn capacity_limited_crane_moves_in_loads() {
+        assert_eq!(CapacityLimitedCrane { capacity: 2 }.arrangement(5), vec![3, 4, 1, 2, 0]);
+
+        let moves = [
+            Move { quantity: 1, from: 1, to: 0 },
+            Move { quantity: 3, from: 0, to: 2 },
+            Move { quantity: 2, from: 1, to: 0 },
+            Move { quantity: 1, from: 0, to: 1 },
+        ];
+        let top_after = |crane: &dyn Crane| {
+            let mut stacks = parse_stacks(&EXAMPLE).unwrap();
+            run_crane(crane, &mut stacks, &moves).unwrap();
+            top_crates(&stacks)
+        };
+        // Carrying one crate is the 9000 and carrying the whole pile is the 9001
+        assert_eq!(top_after(&CapacityLimitedCrane { capacity: 1 }), "CMZ");
+        assert_eq!(top_after(&CapacityLimitedCrane { capacity: 3 }), "MCD");
+        assert_eq!(top_after(&CapacityLimitedCrane { capacity: 2 }), "MCZ");
+    }
+
+    #[test]
+    fn reversing_moves_restores_the_start() {
+        let initial = parse_stacks(&EXAMPLE).unwrap();
+        let moves = [
+            Move { quantity: 1, from: 1, to: 0 },
+            Move { quantity: 3, from: 0, to: 2 },
+            Move { quantity: 2, from: 1, to: 0 },
+            Move { quantity: 1, from: 0, to: 1 },
+        ];
+        let cranes: [&dyn Crane; 4] = [
+            &CrateMover9000,
+            &CrateMover9001,
+            &CapacityLimitedCrane { capacity: 2 },
+            &CapacityLimitedCrane { capacity: 3 },
+        ];
+        for crane in cranes {
+            let mut stacks = initial.clone();
+            run_crane(crane, &mut stacks, &moves).unwrap();
+            assert_eq!(reconstruct_initial(crane, &stacks, &moves).unwrap(), initial, "{}", crane.name());
+        }
+    }
+
+    #[test]
+    fn reversing_impossible_moves_fails() {
+        let final_stacks = vec![vec!['A'], vec![]];
+        let moves = [Move { quantity: 2, from: 0, to: 1 }];
+        assert!(reconstruct_initial(&CrateMover9000, &final_stacks, &moves).is_err());
+    }
+
+    #[test]
+    fn round_trips_many_stacks() {
+        let stacks: Stacks = (0..12)
+            .map(|i| (0..i % 4).map(|j| (b'A' + (i + j) as u8) as char).collect())
+            .collect();
+        let drawing = render_stacks(&stacks);
+        assert!(drawing.last().unwrap().ends_with(" 11  12"));
+        assert_eq!(parse_stacks(&drawing).unwrap(), stacks);
+    }
+
+    #[test]
+    fn parses_ragged_lines_without_numbers() {
+        let lines = ["    [D]", "[N] [C]", "[Z] [M] [P]", ""];
+        let stacks = parse_stacks(&lines).unwrap();
+        assert_eq!(stacks, vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+    }
+
+    #[test]
+    fn rejects_bad_drawings() {
+        assert!(parse_stacks(&["[A]    ", "    [B]", " 1   2 "]).is_err());
+        assert!(parse_stacks(&["[A] x", " 1   2 "]).is_err());
+        assert!(parse_stacks(&["[A]", " 2 "]).is_err());
+    }
+
+    #[test]
+    fn rejects_multi_character_labels() {
+        assert_eq!(
+            parse_stacks(&["    [AB]", "[C] [D] ", " 1   2  "]),
+            Err("Crate labels must be a single character, not '[AB]' at row 1, column 5 of the drawing".to_string())
+        );
+        assert!(parse_stacks(&["[ABC]", " 1 "]).is_err());
+        // A lone bracket is still just unexpected
+        assert_eq!(
+            parse_stacks(&["[A", " 1 "]),
+            Err("Unexpected '[' at row 1, column 1 of the drawing".to_string())
+        );
+    }
+}
@@ -2,6 +2,9 @@ use std::fs;
 use std::path::PathBuf;
 use std::collections::HashSet;
 
+use crate::utils::tui::{self, Simulation};
+use crate::utils::visualize::{Frame, Visualizer};
+
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 struct Position {
     x: i32,
@@ -32,7 +35,7 @@ impl Position {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Rope {
     knots: Vec<Position>,
     visited_positions: HashSet<Position>,
@@ -108,6 +111,128 @@ fn solve_part2(input_text: &str) -> usize {
     rope.visited_positions.len()
 }
 
+/// Saves the positions visited by the tail and an animation of the rope moving
+fn visualize_rope(visualizer: &Visualizer, name: &str, moves: &[(char, u32)], num_knots: usize) {
+    let mut rope = Rope::new(num_knots);
+    let mut snapshots = vec![rope.knots.clone()];
+    for &(direction, steps) in moves {
+        for _ in 0..steps {
+            rope.move_head(direction, 1);
+            snapshots.push(rope.knots.clone());
+        }
+    }
+
+    let all_knots = || snapshots.iter().flatten();
+    let min_x = all_knots().map(|p| p.x).min().unwrap();
+    let max_x = all_knots().map(|p| p.x).max().unwrap();
+    let min_y = all_knots().map(|p| p.y).min().unwrap();
+    let max_y = all_knots().map(|p| p.y).max().unwrap();
+    let to_pixel = |p: &Position| ((p.x - min_x) as usize, (p.y - min_y) as usize);
+
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut trail = Frame::new(width, height, [20, 24, 40]);
+    let mut frames = Vec::new();
+    // Keep the animation to a couple of hundred frames
+    let interval = (snapshots.len() / 200).max(1);
+
+    for (step, knots) in snapshots.iter().enumerate() {
+        let (x, y) = to_pixel(knots.last().unwrap());
+        trail.set(x, y, [80, 160, 220]);
+
+        if step % interval == 0 || step == snapshots.len() - 1 {
+            let mut frame = trail.clone();
+            // Draw the tail first so the head stays on top
+            for (i, knot) in knots.iter().enumerate().rev() {
+                let (x, y) = to_pixel(knot);
+                let color = if i == 0 { [230, 70, 60] } else { [240, 240, 240] };
+                frame.set(x, y, color);
+            }
+            frames.push(frame.scaled(2));
+        }
+    }
+
+    visualizer.save_image(name, &trail.scaled(2));
+    visualizer.save_animation(name, &frames, 4);
+}
+
+/// Moves the head one step at a time in the terminal UI
+#[derive(Clone)]
+struct RopeSimulation {
+    title: &'static str,
+    rope: Rope,
+    moves: Vec<(char, u32)>,
+    next_move: usize,
+    steps_into_move: u32,
+}
+
+impl Simulation for RopeSimulation {
+    fn title(&self) -> String {
+        self.title.to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        // Skip past moves that are complete
+        while let Some(&(_, steps)) = self.moves.get(self.next_move) {
+            if self.steps_into_move < steps {
+                break;
+            }
+            self.next_move += 1;
+            self.steps_into_move = 0;
+        }
+        let Some(&(direction, _)) = self.moves.get(self.next_move) else {
+            return false;
+        };
+        self.rope.move_head(direction, 1);
+        self.steps_into_move += 1;
+        true
+    }
+
+    fn render(&self, width: usize, height: usize) -> Vec<String> {
+        // Keep the head in the middle of the view
+        let head = self.rope.knots[0];
+        let left = head.x - width as i32 / 2;
+        let top = head.y - height as i32 / 2;
+        let last = self.rope.knots.len() - 1;
+
+        (0..height as i32)
+            .map(|row| {
+                (0..width as i32)
+                    .map(|col| {
+                        let position = Position::new(left + col, top + row);
+                        match self.rope.knots.iter().position(|&k| k == position) {
+                            Some(0) => 'H',
+                            Some(i) if i == last && last == 1 => 'T',
+                            Some(i) => char::from_digit(i as u32, 10).unwrap_or('*'),
+                            None if position == Position::new(0, 0) => 's',
+                            None if self.rope.visited_positions.contains(&position) => '#',
+                            None => '.',
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn inspect(&self) -> Vec<(String, String)> {
+        let current_move = match self.moves.get(self.next_move) {
+            Some(&(direction, steps)) => {
+                format!("{} {} ({}/{})", direction, steps, self.steps_into_move, steps)
+            }
+            None => "-".to_string(),
+        };
+        let head = self.rope.knots[0];
+        let tail = self.rope.knots[self.rope.knots.len() - 1];
+        vec![
+            ("Move".to_string(), format!("{} / {}", self.next_move + 1, self.moves.len())),
+            ("Current".to_string(), current_move),
+            ("Head".to_string(), format!("({}, {})", head.x, head.y)),
+            ("Tail".to_string(), format!("({}, {})", tail.x, tail.y)),
+            ("Visited".to_string(), self.rope.visited_positions.len().to_string()),
+        ]
+    }
+}
+
 pub fn solve() {
     println!("Problem 9");
     let input_path = PathBuf::from("src/problems/year_2022/problem_9/input.txt");
@@ -116,4 +241,25 @@ pub fn solve() {
     let result2 = solve_part2(&input_text);
     println!("Part 1: {}", result1);
     println!("Part 2: {}", result2);
+
+    if let Some(visualizer) = Visualizer::from_args("2022_day_9") {
+        let moves = parse_input(&input_text);
+        visualize_rope(&visualizer, "part1", &moves, 2);
+        visualize_rope(&visualizer, "part2", &moves, 10);
+    }
+
+    if tui::requested() {
+        let moves = parse_input(&input_text);
+        let simulation = |title, num_knots| RopeSimulation {
+            title,
+            rope: Rope::new(num_knots),
+            moves: moves.clone(),
+            next_move: 0,
+            steps_into_move: 0,
+        };
+        let simulations = vec![simulation("2 knots", 2), simulation("10 knots", 10)];
+        if let Err(e) = tui::run(simulations) {
+            eprintln!("Terminal UI failed: {}", e);
+        }
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A repeating stretch of a state sequence: the state after `start` steps is
+/// seen again after `start + period` steps, and from then on forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` onto the earliest step with the same state
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// Looks up the state after `n` steps in a history that covers at least
+    /// steps `0..start + period` (`history[i]` is the state after `i` steps)
+    #[allow(dead_code)]
+    pub fn state_at<'a, T>(&self, history: &'a [T], n: usize) -> &'a T {
+        &history[self.equivalent_step(n)]
+    }
+
+    /// Extrapolates a metric that grows by the same amount every period, such as
+    /// a counter or a height. `values[i]` is the metric after `i` steps and must
+    /// cover at least steps `0..=start + period`.
+    pub fn extrapolate_metric(&self, values: &[i64], n: usize) -> i64 {
+        if n < self.start {
+            return values[n];
+        }
+        let periods = ((n - self.start) / self.period) as i64;
+        let gain_per_period = values[self.start + self.period] - values[self.start];
+        values[self.equivalent_step(n)] + periods * gain_per_period
+    }
+}
+
+/// Finds the cycle of `x, f(x), f(f(x)), ...` with Floyd's tortoise and hare.
+/// Only two states are kept in memory, at the cost of more calls to `step`.
+#[allow(dead_code)]
+pub fn floyd<T, F>(initial: T, step: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> T,
+{
+    let mut tortoise = step(&initial);
+    let mut hare = step(&step(&initial));
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // Find the start of the cycle
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    // Find the period
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Finds the cycle of `x, f(x), f(f(x)), ...` with Brent's algorithm, which
+/// usually needs fewer calls to `step` than Floyd's.
+#[allow(dead_code)]
+pub fn brent<T, F>(initial: T, step: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> T,
+{
+    // Find the period by teleporting the tortoise at powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Start the hare one period ahead and walk both until they meet
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Detects a cycle by remembering every state seen so far. Suited to
+/// simulations that are stepped in place and cannot be cheaply cloned or
+/// rewound, where the caller feeds in a key describing each state.
+#[derive(Debug)]
+pub struct HashCycleDetector<K> {
+    seen: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> Default for HashCycleDetector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq> HashCycleDetector<K> {
+    pub fn new() -> Self {
+        HashCycleDetector {
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Records the key of the state after the next step (the first call is
+    /// step 0) and returns the cycle as soon as a key repeats
+    pub fn observe(&mut self, key: K) -> Option<Cycle> {
+        let steps = self.seen.len();
+        match self.seen.get(&key) {
+            Some(&start) => Some(Cycle {
+                start,
+                period: steps - start,
+            }),
+            None => {
+                self.seen.insert(key, steps);
+                None
+            }
+        }
+    }
+}
+
+/// Runs `step` from `initial` until a state repeats, returning the cycle and
+/// every state visited up to and including the repeat at `start + period`
+#[allow(dead_code)]
+pub fn find_cycle<T, F>(initial: T, step: F) -> (Cycle, Vec<T>)
+where
+    T: Clone + Hash + Eq,
+    F: Fn(&T) -> T,
+{
+    let mut detector = HashCycleDetector::new();
+    let mut history = vec![initial];
+    loop {
+        let current = history.last().unwrap();
+        if let Some(cycle) = detector.observe(current.clone()) {
+            return (cycle, history);
+        }
+        let next = step(current);
+        history.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sequence that walks `start` steps into a loop of length `period`
+    fn rho(start: usize, period: usize) -> impl Fn(&usize) -> usize {
+        move |&x| if x + 1 < start + period { x + 1 } else { start }
+    }
+
+    const SHAPES: [(usize, usize); 5] = [(0, 1), (3, 1), (7, 4), (1, 13), (0, 6)];
+
+    #[test]
+    fn floyd_and_brent_find_start_and_period() {
+        for (start, period) in SHAPES {
+            let expected = Cycle { start, period };
+            assert_eq!(floyd(0, rho(start, period)), expected);
+            assert_eq!(brent(0, rho(start, period)), expected);
+        }
+    }
+
+    #[test]
+    fn hash_detector_matches() {
+        for (start, period) in SHAPES {
+            let step = rho(start, period);
+            let mut detector = HashCycleDetector::new();
+            let mut state = 0;
+            let cycle = loop {
+                if let Some(cycle) = detector.observe(state) {
+                    break cycle;
+                }
+                state = step(&state);
+            };
+            assert_eq!(cycle, Cycle { start, period });
+        }
+    }
+
+    #[test]
+    fn find_cycle_keeps_history_up_to_the_repeat() {
+        let (cycle, history) = find_cycle(0, rho(7, 4));
+        assert_eq!(cycle, Cycle { start: 7, period: 4 });
+        assert_eq!(history, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 7]);
+        assert_eq!(*cycle.state_at(&history, 5), 5);
+        assert_eq!(*cycle.state_at(&history, 1_000_000), 7 + (1_000_000 - 7) % 4);
+    }
+
+    #[test]
+    fn extrapolates_steps_and_metrics() {
+        let cycle = Cycle { start: 2, period: 3 };
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(2), 2);
+        assert_eq!(cycle.equivalent_step(9), 3);
+
+        // Grows by 10 every period after a two-step run-up
+        let values = [0, 1, 5, 8, 11, 15];
+        assert_eq!(cycle.extrapolate_metric(&values, 1), 1);
+        assert_eq!(cycle.extrapolate_metric(&values, 5), 15);
+        assert_eq!(cycle.extrapolate_metric(&values, 9), 8 + 20);
+    }
+}
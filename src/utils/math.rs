@@ -0,0 +1,187 @@
+/// Greatest common divisor, always non-negative
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if the result does not fit in an `i64`
+pub fn checked_lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a.abs() / gcd(a, b)).checked_mul(b.abs())
+}
+
+/// Least common multiple of two numbers
+pub fn lcm(a: i64, b: i64) -> i64 {
+    checked_lcm(a, b).expect("lcm overflowed i64")
+}
+
+/// Least common multiple of every value, 1 for an empty input
+pub fn lcm_all<I: IntoIterator<Item = i64>>(values: I) -> i64 {
+    values.into_iter().fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`
+#[allow(dead_code)]
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    let (mut old_t, mut t) = (0i128, 1i128);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < 0 {
+        (old_r, old_s, old_t) = (-old_r, -old_s, -old_t);
+    }
+    (old_r as i64, old_s as i64, old_t as i64)
+}
+
+/// Euclidean remainder, always in `0..modulus`
+#[allow(dead_code)]
+pub fn rem(a: i64, modulus: i64) -> i64 {
+    a.rem_euclid(modulus)
+}
+
+/// `(a + b) mod modulus` without intermediate overflow
+#[allow(dead_code)]
+pub fn add_mod(a: i64, b: i64, modulus: i64) -> i64 {
+    ((a as i128 + b as i128).rem_euclid(modulus as i128)) as i64
+}
+
+/// `(a * b) mod modulus` without intermediate overflow
+#[allow(dead_code)]
+pub fn mul_mod(a: i64, b: i64, modulus: i64) -> i64 {
+    ((a as i128 * b as i128).rem_euclid(modulus as i128)) as i64
+}
+
+/// `base^exp mod modulus` by repeated squaring
+#[allow(dead_code)]
+pub fn mod_pow(base: i64, mut exp: u64, modulus: i64) -> i64 {
+    if modulus == 1 {
+        return 0;
+    }
+    let mut result = 1;
+    let mut base = rem(base, modulus);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Multiplicative inverse of `a` modulo `modulus`, if `a` and `modulus` are coprime
+#[allow(dead_code)]
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(rem(a, modulus), modulus);
+    if g != 1 {
+        return None;
+    }
+    Some(rem(x, modulus))
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs.
+///
+/// The moduli do not need to be pairwise coprime. Returns the smallest
+/// non-negative solution together with the combined modulus, or `None`
+/// if the congruences are inconsistent or the combined modulus overflows.
+#[allow(dead_code)]
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut result = 0;
+    let mut combined = 1;
+
+    for &(residue, modulus) in congruences {
+        let residue = rem(residue, modulus);
+        let (g, p, _) = extended_gcd(combined, modulus);
+        let diff = residue - result;
+        if diff % g != 0 {
+            return None;
+        }
+
+        // Solve combined * k == diff (mod modulus) for k
+        let step = modulus / g;
+        let k = mul_mod(diff / g, p, step);
+        let next = checked_lcm(combined, modulus)?;
+        result = add_mod(result, mul_mod(combined, k, next), next);
+        combined = next;
+    }
+
+    Some((result, combined))
+}
+
+/// Sum of every value, or `None` on overflow
+#[allow(dead_code)]
+pub fn checked_sum<I: IntoIterator<Item = i64>>(values: I) -> Option<i64> {
+    values.into_iter().try_fold(0i64, |acc, v| acc.checked_add(v))
+}
+
+/// Product of every value, or `None` on overflow
+#[allow(dead_code)]
+pub fn checked_product<I: IntoIterator<Item = i64>>(values: I) -> Option<i64> {
+    values.into_iter().try_fold(1i64, |acc, v| acc.checked_mul(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(lcm_all([23, 19, 13, 17]), 96577);
+        assert_eq!(lcm_all([2, 4, 8]), 8);
+        assert_eq!(checked_lcm(i64::MAX, i64::MAX - 1), None);
+    }
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for &(a, b) in &[(240, 46), (17, 5), (-30, 12), (0, 9)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(5, 3, 1), 0);
+        assert_eq!(mod_pow(i64::MAX, 2, i64::MAX - 1), 1);
+        assert_eq!(mul_mod(i64::MAX, i64::MAX, 1_000_000_007), 737_564_071);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn checked_folds() {
+        assert_eq!(checked_sum([1, 2, 3]), Some(6));
+        assert_eq!(checked_sum([i64::MAX, 1]), None);
+        assert_eq!(checked_product([2, 3, 7]), Some(42));
+        assert_eq!(checked_product([i64::MAX, 2]), None);
+    }
+}
@@ -0,0 +1,9 @@
+// Helpers shared between puzzle solutions. Helpers that no solved day calls
+// yet carry their own `#[allow(dead_code)]`; drop it once a puzzle uses them.
+
+pub mod cycle;
+pub mod math;
+pub mod ocr;
+pub mod snafu;
+pub mod tui;
+pub mod visualize;
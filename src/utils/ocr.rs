@@ -0,0 +1,191 @@
+/// The letter sizes used by screen-rendering puzzles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    /// 4 pixels wide, 6 tall, one blank column between letters
+    Small,
+    /// 6 pixels wide, 10 tall, two blank columns between letters
+    Large,
+}
+
+impl Font {
+    fn width(self) -> usize {
+        match self {
+            Font::Small => 4,
+            Font::Large => 6,
+        }
+    }
+
+    fn height(self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    fn stride(self) -> usize {
+        match self {
+            Font::Small => 5,
+            Font::Large => 8,
+        }
+    }
+
+    fn glyphs(self) -> &'static [(char, &'static [&'static str])] {
+        match self {
+            Font::Small => SMALL_GLYPHS,
+            Font::Large => LARGE_GLYPHS,
+        }
+    }
+
+    fn for_height(height: usize) -> Option<Font> {
+        [Font::Small, Font::Large]
+            .into_iter()
+            .find(|font| font.height() == height)
+    }
+}
+
+const SMALL_GLYPHS: &[(char, &[&str])] = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_GLYPHS: &[(char, &[&str])] = &[
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the letters drawn in a screen of `#` (lit) and any other character
+/// (dark), picking the font from the number of rows
+pub fn recognise(screen: &str) -> Result<String, String> {
+    let grid: Vec<Vec<bool>> = screen
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect();
+    recognise_grid(&grid)
+}
+
+/// Reads the letters drawn in a grid of lit pixels, picking the font from the
+/// number of rows
+pub fn recognise_grid(grid: &[Vec<bool>]) -> Result<String, String> {
+    let font = Font::for_height(grid.len())
+        .ok_or_else(|| format!("No font is {} pixels tall", grid.len()))?;
+    recognise_with_font(grid, font)
+}
+
+/// Reads the letters drawn in a grid of lit pixels using the given font
+pub fn recognise_with_font(grid: &[Vec<bool>], font: Font) -> Result<String, String> {
+    if grid.len() != font.height() {
+        return Err(format!(
+            "Expected {} rows for this font, found {}",
+            font.height(),
+            grid.len()
+        ));
+    }
+
+    let pixel = |row: usize, col: usize| grid[row].get(col).copied().unwrap_or(false);
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let mut letters = String::new();
+
+    for left in (0..width).step_by(font.stride()) {
+        let cell: Vec<Vec<bool>> = (0..font.height())
+            .map(|row| (left..left + font.width()).map(|col| pixel(row, col)).collect())
+            .collect();
+
+        // Screens may be wider than the text they show
+        if cell.iter().flatten().all(|&lit| !lit) {
+            continue;
+        }
+
+        let letter = font
+            .glyphs()
+            .iter()
+            .find(|(_, rows)| {
+                rows.iter().zip(&cell).all(|(glyph_row, cell_row)| {
+                    glyph_row.chars().map(|c| c == '#').eq(cell_row.iter().copied())
+                })
+            })
+            .map(|&(letter, _)| letter)
+            .ok_or_else(|| format!("Unrecognised glyph at column {}", left))?;
+        letters.push(letter);
+    }
+
+    Ok(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_small_font() {
+        // The day 10 CRT
+        let screen = "\
+####.####.###..###..###..####.####.####.
+#.......#.#..#.#..#.#..#.#.......#.#....
+###....#..###..#..#.###..###....#..###..
+#.....#...#..#.###..#..#.#.....#...#....
+#....#....#..#.#....#..#.#....#....#....
+#....####.###..#....###..#....####.#....";
+        assert_eq!(recognise(screen), Ok("FZBPBFZF".to_string()));
+    }
+
+    #[test]
+    fn reads_large_font() {
+        let screen = [
+            "#....#..######..#....#..#####.",
+            "##...#.......#..#....#..#....#",
+            "##...#.......#...#..#...#....#",
+            "#.#..#......#....#..#...#....#",
+            "#.#..#.....#......##....#####.",
+            "#..#.#....#.......##....#..#..",
+            "#..#.#...#.......#..#...#...#.",
+            "#...##..#........#..#...#...#.",
+            "#...##..#.......#....#..#....#",
+            "#....#..######..#....#..#....#",
+        ]
+        .join("\n");
+        assert_eq!(recognise(&screen), Ok("NZXR".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_glyphs_and_sizes() {
+        let screen = "\
+####.#..#
+#..#.#..#
+#..#.####
+#..#.#..#
+#..#.#..#
+####.#..#";
+        assert_eq!(recognise(screen), Err("Unrecognised glyph at column 0".to_string()));
+        assert!(recognise("#\n#\n#").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// A number written in SNAFU: base 5 with the digits `=` (-2), `-` (-1), 0, 1 and 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Snafu(i64);
+
+impl Snafu {
+    pub fn value(self) -> i64 {
+        self.0
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(value: i64) -> Self {
+        Snafu(value)
+    }
+}
+
+impl FromStr for Snafu {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Empty SNAFU number".to_string());
+        }
+        s.chars().try_fold(Snafu(0), |acc, c| {
+            let digit = match c {
+                '2' => 2,
+                '1' => 1,
+                '0' => 0,
+                '-' => -1,
+                '=' => -2,
+                _ => return Err(format!("Invalid SNAFU digit '{}' in '{}'", c, s)),
+            };
+            acc.0
+                .checked_mul(5)
+                .and_then(|v| v.checked_add(digit))
+                .map(Snafu)
+                .ok_or_else(|| format!("SNAFU number '{}' is too large", s))
+        })
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0");
+        }
+        // Negative numbers are the positive digits with every digit negated
+        let negate = self.0 < 0;
+        let mut n = self.0.unsigned_abs() as i128;
+        let mut digits = Vec::new();
+        while n != 0 {
+            // Digits 3 and 4 become -2 and -1 with a carry into the next place
+            let digit = ((n + 2) % 5) - 2;
+            n = (n - digit) / 5;
+            let digit = if negate { -digit } else { digit };
+            digits.push(match digit {
+                2 => '2',
+                1 => '1',
+                0 => '0',
+                -1 => '-',
+                _ => '=',
+            });
+        }
+        let text: String = digits.iter().rev().collect();
+        write!(f, "{}", text)
+    }
+}
+
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: Snafu) -> Snafu {
+        Snafu(self.0 + other.0)
+    }
+}
+
+impl Sub for Snafu {
+    type Output = Snafu;
+
+    fn sub(self, other: Snafu) -> Snafu {
+        Snafu(self.0 - other.0)
+    }
+}
+
+impl Mul for Snafu {
+    type Output = Snafu;
+
+    fn mul(self, other: Snafu) -> Snafu {
+        Snafu(self.0 * other.0)
+    }
+}
+
+impl Neg for Snafu {
+    type Output = Snafu;
+
+    fn neg(self) -> Snafu {
+        Snafu(-self.0)
+    }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Snafu>>(iter: I) -> Snafu {
+        iter.fold(Snafu(0), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        let table = [
+            (1, "1"),
+            (2, "2"),
+            (3, "1="),
+            (4, "1-"),
+            (5, "10"),
+            (8, "2="),
+            (20, "1-0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ];
+        for (value, text) in table {
+            assert_eq!(Snafu::from(value).to_string(), text);
+            assert_eq!(text.parse::<Snafu>(), Ok(Snafu::from(value)));
+        }
+    }
+
+    #[test]
+    fn round_trips_negative_and_positive_values() {
+        for value in -3000..=3000 {
+            let snafu = Snafu::from(value);
+            assert_eq!(snafu.to_string().parse::<Snafu>(), Ok(snafu), "{}", value);
+        }
+        assert_eq!(Snafu::from(-3).to_string(), "-2");
+        for value in [i64::MAX, i64::MIN + 1] {
+            assert_eq!(
+                Snafu::from(value)
+                    .to_string()
+                    .parse::<Snafu>()
+                    .map(Snafu::value),
+                Ok(value)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!("".parse::<Snafu>().is_err());
+        assert!("13".parse::<Snafu>().is_err());
+        assert!("2222222222222222222222222222".parse::<Snafu>().is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a: Snafu = "1=".parse().unwrap();
+        let b: Snafu = "2-".parse().unwrap();
+        assert_eq!(a + b, Snafu::from(12));
+        assert_eq!(a - b, Snafu::from(-6));
+        assert_eq!(a * b, Snafu::from(27));
+        assert_eq!(-a, Snafu::from(-3));
+        assert_eq!([a, b, a].into_iter().sum::<Snafu>(), Snafu::from(15));
+    }
+}
@@ -0,0 +1,303 @@
+use std::env;
+use std::io;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+
+/// A simulation that can be stepped through in the terminal UI
+pub trait Simulation: Clone {
+    /// Short name shown in the tab bar
+    fn title(&self) -> String;
+
+    /// Advances one step, returning `false` if the simulation had already finished
+    fn step(&mut self) -> bool;
+
+    /// Draws the current state in at most `width` x `height` characters
+    fn render(&self, width: usize, height: usize) -> Vec<String>;
+
+    /// Labelled values describing the current state
+    fn inspect(&self) -> Vec<(String, String)>;
+}
+
+/// Returns true if `--tui` was passed on the command line
+pub fn requested() -> bool {
+    env::args().any(|arg| arg == "--tui")
+}
+
+/// Steps between saved copies of the state. Stepping back restores the
+/// nearest earlier copy and replays forward from there.
+const CHECKPOINT_INTERVAL: usize = 100;
+
+struct Player<S> {
+    checkpoints: Vec<S>,
+    current: S,
+    steps: usize,
+    finished: bool,
+}
+
+impl<S: Simulation> Player<S> {
+    fn new(initial: S) -> Self {
+        Player {
+            checkpoints: vec![initial.clone()],
+            current: initial,
+            steps: 0,
+            finished: false,
+        }
+    }
+
+    fn forward(&mut self) -> bool {
+        if !self.current.step() {
+            self.finished = true;
+            return false;
+        }
+        self.steps += 1;
+        if self.steps.is_multiple_of(CHECKPOINT_INTERVAL) && self.checkpoints.len() == self.steps / CHECKPOINT_INTERVAL {
+            self.checkpoints.push(self.current.clone());
+        }
+        true
+    }
+
+    fn seek(&mut self, target: usize) {
+        if target < self.steps {
+            let index = (target / CHECKPOINT_INTERVAL).min(self.checkpoints.len() - 1);
+            self.current = self.checkpoints[index].clone();
+            self.steps = index * CHECKPOINT_INTERVAL;
+            self.finished = false;
+        }
+        while self.steps < target && self.forward() {}
+    }
+}
+
+struct App<S> {
+    players: Vec<Player<S>>,
+    selected: usize,
+    playing: bool,
+    delay: Duration,
+}
+
+impl<S: Simulation> App<S> {
+    fn player(&mut self) -> &mut Player<S> {
+        &mut self.players[self.selected]
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let timeout = if self.playing {
+                self.delay
+            } else {
+                Duration::from_secs(3600)
+            };
+            if !event::poll(timeout)? {
+                if self.playing && !self.player().forward() {
+                    self.playing = false;
+                }
+                continue;
+            }
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char(' ') => self.playing = !self.playing,
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.player().forward();
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    let steps = self.player().steps;
+                    self.player().seek(steps.saturating_sub(1));
+                }
+                KeyCode::PageDown => {
+                    let steps = self.player().steps;
+                    self.player().seek(steps + 100);
+                }
+                KeyCode::PageUp => {
+                    let steps = self.player().steps;
+                    self.player().seek(steps.saturating_sub(100));
+                }
+                KeyCode::Home => self.player().seek(0),
+                KeyCode::Up | KeyCode::Char('+') => self.delay = (self.delay / 2).max(Duration::from_millis(1)),
+                KeyCode::Down | KeyCode::Char('-') => self.delay = (self.delay * 2).min(Duration::from_secs(2)),
+                KeyCode::Tab => {
+                    self.selected = (self.selected + 1) % self.players.len();
+                    self.playing = false;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [tabs_area, main_area, help_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [state_area, inspect_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(36)]).areas(main_area);
+
+        let titles = self.players.iter().map(|p| p.current.title());
+        let tabs = Tabs::new(titles)
+            .select(self.selected)
+            .highlight_style(Style::new().bold().reversed());
+        frame.render_widget(tabs, tabs_area);
+
+        let player = &self.players[self.selected];
+        let status = if player.finished {
+            "finished"
+        } else if self.playing {
+            "playing"
+        } else {
+            "paused"
+        };
+        let state_block = Block::bordered().title(format!(" Step {} ({}) ", player.steps, status));
+        let inner = state_block.inner(state_area);
+        let lines: Vec<Line> = player
+            .current
+            .render(inner.width as usize, inner.height as usize)
+            .into_iter()
+            .map(Line::from)
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(state_block), state_area);
+
+        let details: Vec<Line> = player
+            .current
+            .inspect()
+            .into_iter()
+            .map(|(label, value)| Line::from(vec![format!("{}: ", label).bold(), value.into()]))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(details).block(Block::bordered().title(" State ")),
+            inspect_area,
+        );
+
+        let help = format!(
+            "←/→ step  PgUp/PgDn ±100  Home restart  space play/pause  ↑/↓ speed ({} ms)  tab next  q quit",
+            self.delay.as_millis()
+        );
+        frame.render_widget(Line::from(help).dim(), help_area);
+    }
+}
+
+/// Opens the terminal UI on the given simulations, one tab each
+pub fn run<S: Simulation>(simulations: Vec<S>) -> io::Result<()> {
+    let mut app = App {
+        players: simulations.into_iter().map(Player::new).collect(),
+        selected: 0,
+        playing: false,
+        delay: Duration::from_millis(100),
+    };
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pseudo-random walk that stops after `limit` steps, so every step
+    /// depends on all the ones before it
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Walk {
+        value: u64,
+        steps: usize,
+        limit: usize,
+    }
+
+    impl Walk {
+        fn new(limit: usize) -> Self {
+            Walk {
+                value: 1,
+                steps: 0,
+                limit,
+            }
+        }
+
+        fn after(limit: usize, steps: usize) -> Self {
+            let mut walk = Walk::new(limit);
+            while walk.steps < steps && walk.step() {}
+            walk
+        }
+    }
+
+    impl Simulation for Walk {
+        fn title(&self) -> String {
+            "Walk".to_string()
+        }
+
+        fn step(&mut self) -> bool {
+            if self.steps == self.limit {
+                return false;
+            }
+            self.value = self.value.wrapping_mul(6364136223846793005).wrapping_add(self.steps as u64);
+            self.steps += 1;
+            true
+        }
+
+        fn render(&self, _width: usize, _height: usize) -> Vec<String> {
+            vec![self.value.to_string()]
+        }
+
+        fn inspect(&self) -> Vec<(String, String)> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn seeking_matches_stepping_forward() {
+        let mut player = Player::new(Walk::new(1000));
+        player.seek(2 * CHECKPOINT_INTERVAL + 50);
+        assert_eq!(player.checkpoints.len(), 3);
+
+        let targets = [
+            CHECKPOINT_INTERVAL - 1,
+            CHECKPOINT_INTERVAL,
+            CHECKPOINT_INTERVAL + 1,
+            0,
+            2 * CHECKPOINT_INTERVAL + 1,
+            CHECKPOINT_INTERVAL + 1,
+            2 * CHECKPOINT_INTERVAL - 1,
+            2 * CHECKPOINT_INTERVAL,
+            3 * CHECKPOINT_INTERVAL + 7,
+        ];
+        for target in targets {
+            player.seek(target);
+            assert_eq!(player.steps, target);
+            assert_eq!(player.current, Walk::after(1000, target), "seeking to {}", target);
+        }
+        // Replaying past a checkpoint doesn't save it again
+        assert_eq!(player.checkpoints.len(), 4);
+        for (i, checkpoint) in player.checkpoints.iter().enumerate() {
+            assert_eq!(*checkpoint, Walk::after(1000, i * CHECKPOINT_INTERVAL));
+        }
+    }
+
+    #[test]
+    fn seeking_stops_at_the_end() {
+        let limit = CHECKPOINT_INTERVAL + 20;
+        let mut player = Player::new(Walk::new(limit));
+        player.seek(10 * CHECKPOINT_INTERVAL);
+        assert_eq!(player.steps, limit);
+        assert!(player.finished);
+        assert!(!player.forward());
+
+        player.seek(CHECKPOINT_INTERVAL - 1);
+        assert!(!player.finished);
+        assert_eq!(player.current, Walk::after(limit, CHECKPOINT_INTERVAL - 1));
+        assert!(player.forward());
+        assert_eq!(player.current, Walk::after(limit, CHECKPOINT_INTERVAL));
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+/// An RGB image, usually one step of a grid simulation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Frame {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// Builds a frame by colouring every `(x, y)` position
+    pub fn from_fn(width: usize, height: usize, color: impl Fn(usize, usize) -> Rgb) -> Self {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| color(x, y))
+            .collect();
+        Frame {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Builds a frame from a row-major grid, one pixel per cell
+    pub fn from_grid<T>(grid: &[Vec<T>], color: impl Fn(&T) -> Rgb) -> Self {
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        Frame::from_fn(width, grid.len(), |x, y| match grid[y].get(x) {
+            Some(cell) => color(cell),
+            None => [0, 0, 0],
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Colours a pixel, ignoring positions outside the frame
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// Enlarges every pixel into a `factor` x `factor` block
+    pub fn scaled(&self, factor: usize) -> Frame {
+        Frame::from_fn(self.width * factor, self.height * factor, |x, y| {
+            self.get(x / factor, y / factor)
+        })
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    /// Writes the frame as a binary PPM (P6) image
+    pub fn write_ppm(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.rgb_bytes())?;
+        out.flush()
+    }
+
+    /// Writes the frame as a PNG image
+    pub fn write_png(&self, path: &Path) -> io::Result<()> {
+        let out = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.rgb_bytes())
+            .map_err(io::Error::other)
+    }
+
+    pub fn write(&self, path: &Path, format: ImageFormat) -> io::Result<()> {
+        match format {
+            ImageFormat::Png => self.write_png(path),
+            ImageFormat::Ppm => self.write_ppm(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationFormat {
+    /// A single looping GIF
+    Gif,
+    /// A directory of numbered still images
+    Frames,
+}
+
+/// Writes frames of equal size as a looping GIF, showing each frame for
+/// `delay` hundredths of a second. All frames together may use at most 256 colours.
+pub fn write_gif(frames: &[Frame], path: &Path, delay: u16) -> io::Result<()> {
+    let first = frames
+        .first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No frames to write"))?;
+    if frames
+        .iter()
+        .any(|f| f.width() != first.width() || f.height() != first.height())
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "All frames of an animation must have the same size",
+        ));
+    }
+    let too_big = |_| io::Error::new(io::ErrorKind::InvalidInput, "Frame is too large for a GIF");
+    let width = u16::try_from(first.width()).map_err(too_big)?;
+    let height = u16::try_from(first.height()).map_err(too_big)?;
+
+    // Share one palette between every frame
+    let mut palette: HashMap<Rgb, u8> = HashMap::new();
+    let mut palette_bytes = Vec::new();
+    let mut indexed_frames = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let mut indices = Vec::with_capacity(frame.pixels.len());
+        // Most neighbouring pixels share a colour, so skip the lookup for runs
+        let mut previous: Option<(Rgb, u8)> = None;
+        for &pixel in &frame.pixels {
+            if let Some((color, index)) = previous {
+                if color == pixel {
+                    indices.push(index);
+                    continue;
+                }
+            }
+            let next_index = palette.len();
+            let index = match palette.get(&pixel) {
+                Some(&index) => index,
+                None => {
+                    let index = u8::try_from(next_index).map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "GIFs are limited to 256 colours")
+                    })?;
+                    palette.insert(pixel, index);
+                    palette_bytes.extend_from_slice(&pixel);
+                    index
+                }
+            };
+            previous = Some((pixel, index));
+            indices.push(index);
+        }
+        indexed_frames.push(indices);
+    }
+
+    let out = BufWriter::new(File::create(path)?);
+    let mut encoder =
+        gif::Encoder::new(out, width, height, &palette_bytes).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    for indices in indexed_frames {
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, indices, None);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+/// Writes every frame to `dir` as `frame_00000.<ext>`, `frame_00001.<ext>`, ...
+pub fn write_frame_dir(frames: &[Frame], dir: &Path, format: ImageFormat) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (i, frame) in frames.iter().enumerate() {
+        let path = dir.join(format!("frame_{:05}.{}", i, format.extension()));
+        frame.write(&path, format)?;
+    }
+    Ok(())
+}
+
+/// Saves the images of one puzzle, as requested on the command line with
+/// `--visualize` or `--visualize=<options>`, where the comma-separated options
+/// are `png` or `ppm` for images and `gif` or `frames` for animations.
+#[derive(Debug)]
+pub struct Visualizer {
+    dir: PathBuf,
+    image_format: ImageFormat,
+    animation_format: AnimationFormat,
+}
+
+impl Visualizer {
+    /// Returns a visualizer writing to `visualizations/<name>` if `--visualize`
+    /// was passed, or `None` when the puzzle should run without images
+    pub fn from_args(name: &str) -> Option<Visualizer> {
+        let options = env::args().find_map(|arg| {
+            if arg == "--visualize" {
+                Some(String::new())
+            } else {
+                arg.strip_prefix("--visualize=").map(String::from)
+            }
+        })?;
+        Some(Visualizer::with_options(name, &options))
+    }
+
+    /// A visualizer writing to `visualizations/<name>` with the given
+    /// comma-separated options, warning about any it doesn't know
+    fn with_options(name: &str, options: &str) -> Visualizer {
+        let mut visualizer = Visualizer {
+            dir: PathBuf::from("visualizations").join(name),
+            image_format: ImageFormat::Png,
+            animation_format: AnimationFormat::Gif,
+        };
+        for option in options.split(',').filter(|o| !o.is_empty()) {
+            match option {
+                "png" => visualizer.image_format = ImageFormat::Png,
+                "ppm" => visualizer.image_format = ImageFormat::Ppm,
+                "gif" => visualizer.animation_format = AnimationFormat::Gif,
+                "frames" => visualizer.animation_format = AnimationFormat::Frames,
+                _ => eprintln!("Warning: Unknown visualization option '{}'", option),
+            }
+        }
+        visualizer
+    }
+
+    /// Saves a single image named `<name>.<ext>`
+    pub fn save_image(&self, name: &str, frame: &Frame) {
+        let path = self
+            .dir
+            .join(format!("{}.{}", name, self.image_format.extension()));
+        let result = fs::create_dir_all(&self.dir).and_then(|_| frame.write(&path, self.image_format));
+        self.report(&path, result);
+    }
+
+    /// Saves an animation as `<name>.gif` or a `<name>/` frame directory
+    pub fn save_animation(&self, name: &str, frames: &[Frame], delay: u16) {
+        let (path, result) = match self.animation_format {
+            AnimationFormat::Gif => {
+                let path = self.dir.join(format!("{}.gif", name));
+                let result = fs::create_dir_all(&self.dir).and_then(|_| write_gif(frames, &path, delay));
+                (path, result)
+            }
+            AnimationFormat::Frames => {
+                let path = self.dir.join(name);
+                let result = write_frame_dir(frames, &path, self.image_format);
+                (path, result)
+            }
+        };
+        self.report(&path, result);
+    }
+
+    fn report(&self, path: &Path, result: io::Result<()>) {
+        match result {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(e) => eprintln!("Failed to save {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+    const RED: Rgb = [255, 0, 0];
+
+    /// An empty directory for one test to write into
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("visualize_{}_{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn builds_frames_from_grids() {
+        let grid = vec![vec![true, false, true], vec![false]];
+        let frame = Frame::from_grid(&grid, |&lit| if lit { WHITE } else { RED });
+        assert_eq!((frame.width(), frame.height()), (3, 2));
+        assert_eq!(frame.get(0, 0), WHITE);
+        assert_eq!(frame.get(1, 0), RED);
+        assert_eq!(frame.get(0, 1), RED);
+        // Ragged rows are padded with black
+        assert_eq!(frame.get(2, 1), BLACK);
+    }
+
+    #[test]
+    fn sets_pixels_inside_the_frame_only() {
+        let mut frame = Frame::new(2, 2, BLACK);
+        frame.set(1, 0, RED);
+        frame.set(2, 0, WHITE);
+        frame.set(0, 5, WHITE);
+        assert_eq!(frame.pixels, vec![BLACK, RED, BLACK, BLACK]);
+    }
+
+    #[test]
+    fn scales_pixels_into_blocks() {
+        let frame = Frame::from_fn(2, 1, |x, _| if x == 0 { RED } else { WHITE });
+        let scaled = frame.scaled(3);
+        assert_eq!((scaled.width(), scaled.height()), (6, 3));
+        for y in 0..3 {
+            for x in 0..6 {
+                assert_eq!(scaled.get(x, y), if x < 3 { RED } else { WHITE });
+            }
+        }
+        assert_eq!(frame.scaled(1), frame);
+    }
+
+    #[test]
+    fn writes_ppm() {
+        let dir = scratch_dir("ppm");
+        let path = dir.join("image.ppm");
+        let frame = Frame::from_fn(3, 2, |x, y| [x as u8, y as u8, 7]);
+        frame.write_ppm(&path).unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        let header = b"P6\n3 2\n255\n";
+        assert!(bytes.starts_with(header));
+        assert_eq!(bytes.len(), header.len() + 3 * 2 * 3);
+        assert_eq!(&bytes[header.len()..header.len() + 6], &[0, 0, 7, 1, 0, 7]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn writes_gif_and_frame_dir() {
+        let dir = scratch_dir("animation");
+        let frames = vec![Frame::new(4, 3, RED), Frame::new(4, 3, WHITE)];
+        write_gif(&frames, &dir.join("animation.gif"), 10).unwrap();
+        assert!(fs::read(dir.join("animation.gif")).unwrap().starts_with(b"GIF89a"));
+
+        write_frame_dir(&frames, &dir.join("frames"), ImageFormat::Ppm).unwrap();
+        assert!(dir.join("frames/frame_00000.ppm").is_file());
+        assert!(dir.join("frames/frame_00001.ppm").is_file());
+        assert!(!dir.join("frames/frame_00002.ppm").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_unwritable_gifs() {
+        let dir = scratch_dir("bad_gif");
+        let path = dir.join("animation.gif");
+
+        let error = write_gif(&[], &path, 10).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        let mismatched = [Frame::new(4, 3, RED), Frame::new(3, 4, RED)];
+        let error = write_gif(&mismatched, &path, 10).unwrap_err();
+        assert_eq!(error.to_string(), "All frames of an animation must have the same size");
+
+        // 257 colours spread over two frames
+        let colorful = [
+            Frame::from_fn(16, 16, |x, y| [x as u8, y as u8, 0]),
+            Frame::from_fn(16, 16, |x, y| [x as u8, y as u8, (x + y == 0) as u8]),
+        ];
+        let error = write_gif(&colorful, &path, 10).unwrap_err();
+        assert_eq!(error.to_string(), "GIFs are limited to 256 colours");
+        assert!(write_gif(&colorful[..1], &path, 10).is_ok());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn parses_options() {
+        let visualizer = Visualizer::with_options("2022_day_9", "");
+        assert_eq!(visualizer.dir, Path::new("visualizations/2022_day_9"));
+        assert_eq!(visualizer.image_format, ImageFormat::Png);
+        assert_eq!(visualizer.animation_format, AnimationFormat::Gif);
+
+        let visualizer = Visualizer::with_options("day", "ppm,frames");
+        assert_eq!(visualizer.image_format, ImageFormat::Ppm);
+        assert_eq!(visualizer.animation_format, AnimationFormat::Frames);
+
+        // Later options win and unknown ones are skipped
+        let visualizer = Visualizer::with_options("day", "frames,,bmp,gif,ppm,png");
+        assert_eq!(visualizer.image_format, ImageFormat::Png);
+        assert_eq!(visualizer.animation_format, AnimationFormat::Gif);
+    }
+}